@@ -0,0 +1,98 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, spanned::Spanned, Data, DeriveInput, Fields, LitInt};
+
+/// Implements `ashen_engine::graphics::vertex::Vertex` for a struct, one
+/// attribute per field. Locations follow the field order unless given with
+/// `#[vertex(location = N)]`; `#[vertex(normalized)]` maps integer fields to
+/// `0.0..1.0` floats and `#[vertex(skip)]` leaves padding fields out.
+#[proc_macro_derive(Vertex, attributes(vertex))]
+pub fn derive_vertex(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match expand_vertex(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand_vertex(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(syn::Error::new(
+                input.span(),
+                "Vertex can only be derived for structs",
+            ))
+        }
+    };
+
+    if matches!(fields, Fields::Unit) {
+        return Err(syn::Error::new(
+            input.span(),
+            "Vertex needs at least one field",
+        ));
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut attributes = Vec::new();
+    let mut next_location = 0u32;
+
+    for (index, field) in fields.iter().enumerate() {
+        let mut location = None;
+        let mut normalized = false;
+        let mut skip = false;
+
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("vertex")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("location") {
+                    location = Some(meta.value()?.parse::<LitInt>()?.base10_parse::<u32>()?);
+                    Ok(())
+                } else if meta.path.is_ident("normalized") {
+                    normalized = true;
+                    Ok(())
+                } else if meta.path.is_ident("skip") {
+                    skip = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `location = N`, `normalized` or `skip`"))
+                }
+            })?;
+        }
+
+        if skip {
+            continue;
+        }
+
+        let location = location.unwrap_or(next_location);
+        next_location = location + 1;
+
+        let ty = &field.ty;
+        let member = match &field.ident {
+            Some(ident) => quote!(#ident),
+            None => {
+                let index = syn::Index::from(index);
+                quote!(#index)
+            }
+        };
+
+        attributes.push(quote! {
+            ::ashen_engine::graphics::vertex::VertexAttributeLayout::of::<#ty>(
+                #location,
+                #normalized,
+                ::std::mem::offset_of!(Self, #member),
+            )
+        });
+    }
+
+    Ok(quote! {
+        impl #impl_generics ::ashen_engine::graphics::vertex::Vertex for #name #ty_generics #where_clause {
+            fn attributes() -> ::std::vec::Vec<::ashen_engine::graphics::vertex::VertexAttributeLayout> {
+                ::std::vec![#(#attributes),*]
+            }
+        }
+    })
+}
@@ -1,84 +1,167 @@
-use std::ffi::CString;
-use std::os::raw::c_void;
-use std::{mem, ptr};
-
-use ashen_engine::graphics::gl_wrapper::{BufferObject, Vao, VertexAttribute};
+use ashen_engine::app::{self, App};
+use ashen_engine::errors::ShaderErrors;
+use ashen_engine::graphics::context::GlContext;
+use ashen_engine::graphics::hot_reload::ShaderWatcher;
+use ashen_engine::graphics::mesh::{Mesh, Topology};
 use ashen_engine::graphics::shaders::Shader;
 use ashen_engine::graphics::textures::{Texture, TextureFiltering, TextureType, TextureWrapping};
+use ashen_engine::graphics::uniform::TextureUnit;
+use ashen_engine::graphics::vertex::Vertex;
+use ashen_engine::input::actions::{Binding, InputMap};
+use ashen_engine::input::events::Key;
 use ashen_engine::logger;
-use ashen_engine::window::Window;
-use cgmath::{vec3, Matrix, Matrix4, Rad, SquareMatrix};
-use gl::types::{GLfloat, GLsizei};
+use ashen_engine::window::{GlProfile, Window};
+use bytemuck::{Pod, Zeroable};
+use cgmath::{vec3, Matrix4, Rad, SquareMatrix};
+
+#[derive(Clone, Copy, Pod, Zeroable, Vertex)]
+#[repr(C)]
+struct TesterVertex {
+    position: [f32; 3],
+    color: [f32; 3],
+    tex_coords: [f32; 2],
+}
 
-fn main() {
-    logger::init();
+impl TesterVertex {
+    fn new(position: [f32; 3], color: [f32; 3], tex_coords: [f32; 2]) -> TesterVertex {
+        TesterVertex {
+            position,
+            color,
+            tex_coords,
+        }
+    }
+}
 
-    let mut window: Window = Window::new(
-        1920,
-        1080,
-        "test",
-        ashen_engine::window::WindowMode::Fullscreen,
-    );
-    let vertices: [f32; 32] = [
-        // positions       // colors        // texture coords
-        0.1, 0.1, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, // top right
-        0.1, -0.1, 0.0, 0.0, 1.0, 0.0, 1.0, 0.0, // bottom right
-        -0.1, -0.1, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, // bottom left
-        -0.1, 0.1, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0, // top left
-    ];
-    let indices = [
-        0, 1, 3, // first Triangle
-        1, 2, 3, // second Triangle
-    ];
+struct Tester {
+    input_map: InputMap,
+    shader: Shader,
+    shader_watcher: ShaderWatcher,
+    quad: Mesh,
+    texture: Texture,
+    texture_2: Texture,
+    elapsed: f64,
+    previous_elapsed: f64,
+    last_report: f64,
+    take_screenshot: bool,
+}
 
-    window.init_gl();
+impl Tester {
+    /// Points the samplers at their texture units.
+    fn bind_samplers(&self) {
+        self.shader.use_program();
+        self.shader.set("texture1", TextureUnit(0));
+        self.shader.set("texture2", TextureUnit(1));
+    }
+}
 
-    let mut shader = Shader::new();
-    shader.load_fragment_shader("engine-tester/assets/shader.fs");
-    shader.load_vertex_shader("engine-tester/assets/shader.vs");
-    shader.link_program();
+impl App for Tester {
+    fn update(&mut self, window: &mut Window, dt: f64) {
+        if self.input_map.was_action_pressed(window.input(), "quit") {
+            window.set_should_close(true);
+        }
+        if self
+            .input_map
+            .was_action_pressed(window.input(), "screenshot")
+        {
+            self.take_screenshot = true;
+        }
 
-    let vao: Vao = Vao::new();
-    vao.bind();
+        self.previous_elapsed = self.elapsed;
+        self.elapsed += dt;
+
+        let time = window.time();
+        if time.total() - self.last_report >= 1.0 {
+            self.last_report = time.total();
+            logger::info!(
+                "{:.1} fps, frame time min {:.2}ms / p99 {:.2}ms / max {:.2}ms",
+                time.fps(),
+                time.min_frame_time() * 1000.0,
+                time.frame_time_percentile(99.0) * 1000.0,
+                time.max_frame_time() * 1000.0,
+            );
+        }
+    }
 
-    let vbo: BufferObject = BufferObject::new(gl::ARRAY_BUFFER, gl::STATIC_DRAW);
-    vbo.bind();
+    fn render(&mut self, window: &mut Window, alpha: f64) {
+        self.shader_watcher.poll([&mut self.shader]);
 
-    vbo.store_f32_data(&vertices);
+        let time = (self.previous_elapsed + (self.elapsed - self.previous_elapsed) * alpha) as f32;
 
-    let ebo: BufferObject = BufferObject::new(gl::ELEMENT_ARRAY_BUFFER, gl::STATIC_DRAW);
-    ebo.bind();
+        unsafe {
+            gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
 
-    ebo.store_i32_data(&indices);
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_2D, self.texture.id());
+            gl::ActiveTexture(gl::TEXTURE1);
+            gl::BindTexture(gl::TEXTURE_2D, self.texture_2.id());
 
-    let stride = 8 * mem::size_of::<GLfloat>() as GLsizei;
+            self.shader.use_program();
+        }
 
-    let position_attrib: VertexAttribute =
-        VertexAttribute::new(0, 3, gl::FLOAT, gl::FALSE, stride, ptr::null());
+        let mut transform: Matrix4<f32> = Matrix4::<f32>::identity();
+        transform = transform * Matrix4::<f32>::from_translation(vec3(0., 0., 0.));
+        transform = transform
+            * Matrix4::<f32>::from_angle_y(Rad(time * 1.))
+            * Matrix4::<f32>::from_angle_x(Rad(time * 0.2))
+            * Matrix4::<f32>::from_angle_z(Rad(time * 0.3));
 
-    let color_attrib: VertexAttribute = VertexAttribute::new(
-        1,
-        3,
-        gl::FLOAT,
-        gl::FALSE,
-        stride,
-        (3 * mem::size_of::<GLfloat>()) as *const c_void,
-    );
+        // render the triangle
+        self.shader.set("transform", transform);
 
-    let tex_cords_attrib: VertexAttribute = VertexAttribute::new(
-        2,
-        2,
-        gl::FLOAT,
-        gl::FALSE,
-        stride,
-        (6 * mem::size_of::<GLfloat>()) as *const c_void,
-    );
+        self.quad.draw();
 
-    position_attrib.enable();
-    color_attrib.enable();
-    tex_cords_attrib.enable();
+        if self.take_screenshot {
+            self.take_screenshot = false;
+            window.save_screenshot_in_background("screenshot.png");
+        }
+    }
+}
+
+fn main() {
+    logger::init();
+
+    let mut window: Window = Window::builder(1920, 1080, "test")
+        .mode(ashen_engine::window::WindowMode::Fullscreen)
+        .context_version(3, 3)
+        .profile(GlProfile::Core)
+        .build()
+        .unwrap_or_else(|e| {
+            logger::error!("{}", e);
+            panic!("Failed to create window");
+        });
+    let input_map = InputMap::load("engine-tester/assets/bindings.toml").unwrap_or_else(|e| {
+        logger::warn!("{}, falling back to default bindings", e);
+
+        let mut input_map = InputMap::new();
+        input_map.bind_action("quit", Binding::Key(Key::Escape));
+        input_map.bind_action("screenshot", Binding::Key(Key::F12));
+        input_map
+    });
+
+    let vertices = [
+        TesterVertex::new([0.1, 0.1, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0]), // top right
+        TesterVertex::new([0.1, -0.1, 0.0], [0.0, 1.0, 0.0], [1.0, 0.0]), // bottom right
+        TesterVertex::new([-0.1, -0.1, 0.0], [0.0, 0.0, 1.0], [0.0, 0.0]), // bottom left
+        TesterVertex::new([-0.1, 0.1, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0]), // top left
+    ];
+    let indices: [u32; 6] = [
+        0, 1, 3, // first Triangle
+        1, 2, 3, // second Triangle
+    ];
+
+    let context = window.init_gl().unwrap_or_else(|e| {
+        logger::error!("{}", e);
+        panic!("Failed to initialize OpenGL");
+    });
+
+    // Compile and link errors are already logged with the full info log.
+    let shader = load_shader(&context).unwrap_or_else(|_| panic!("Failed to build shader"));
+
+    let quad = Mesh::indexed(&context, &vertices, &indices, Topology::Triangles);
 
     let texture: Texture = Texture::new(
+        &context,
         "engine-tester/assets/wall.jpg",
         TextureType::Texture2D,
         TextureWrapping::Mirror,
@@ -87,6 +170,7 @@ fn main() {
     );
 
     let texture_2: Texture = Texture::new(
+        &context,
         "engine-tester/assets/awesomeface.png",
         TextureType::Texture2D,
         TextureWrapping::Mirror,
@@ -94,49 +178,29 @@ fn main() {
         TextureFiltering::None,
     );
 
-    let shader = unsafe {
-        shader.use_program();
-
-        texture.set_uniform("texture1", shader.id, 0);
-        texture_2.set_uniform("texture2", shader.id, 1);
-
-        shader
+    let mut tester = Tester {
+        input_map,
+        shader,
+        shader_watcher: ShaderWatcher::default(),
+        quad,
+        texture,
+        texture_2,
+        elapsed: 0.0,
+        previous_elapsed: 0.0,
+        last_report: 0.0,
+        take_screenshot: false,
     };
 
-    let transform_loc_name = CString::new("transform").unwrap();
-
-    while !window.should_close() {
-        unsafe {
-            gl::ClearColor(0.2, 0.3, 0.3, 1.0);
-            gl::Clear(gl::COLOR_BUFFER_BIT);
+    tester.bind_samplers();
 
-            gl::ActiveTexture(gl::TEXTURE0);
-            gl::BindTexture(gl::TEXTURE_2D, texture.id);
-            gl::ActiveTexture(gl::TEXTURE1);
-            gl::BindTexture(gl::TEXTURE_2D, texture_2.id);
-
-            let mut transform: Matrix4<f32> = Matrix4::<f32>::identity();
-            transform = transform * Matrix4::<f32>::from_translation(vec3(0., 0., 0.));
-            transform = transform
-                * Matrix4::<f32>::from_angle_y(Rad(window.glfw.get_time() as f32 * 1.))
-                * Matrix4::<f32>::from_angle_x(Rad(window.glfw.get_time() as f32 * 0.2))
-                * Matrix4::<f32>::from_angle_z(Rad(window.glfw.get_time() as f32 * 0.3));
-
-            // render the triangle
-            shader.use_program();
-            let transform_loc = gl::GetUniformLocation(shader.id, transform_loc_name.as_ptr());
-            gl::UniformMatrix4fv(transform_loc, 1, gl::FALSE, transform.as_ptr());
-
-            gl::BindVertexArray(vao.id);
-            gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, ptr::null());
-        }
+    app::run(&mut window, &mut tester);
+}
 
-        window.update();
-    }
+fn load_shader(context: &GlContext) -> Result<Shader, ShaderErrors> {
+    let mut shader = Shader::new(context);
+    shader.load_fragment_shader("engine-tester/assets/shader.fs")?;
+    shader.load_vertex_shader("engine-tester/assets/shader.vs")?;
+    shader.link_program()?;
 
-    unsafe {
-        gl::DeleteBuffers(1, &vao.id);
-        gl::DeleteBuffers(1, &vbo.id);
-        gl::DeleteBuffers(1, &ebo.id);
-    }
+    Ok(shader)
 }
@@ -0,0 +1,91 @@
+use crate::window::Window;
+
+pub trait App {
+    /// Advances the simulation by exactly `dt` seconds. May run zero or
+    /// several times per rendered frame.
+    fn update(&mut self, window: &mut Window, dt: f64);
+
+    /// Draws the current state. `alpha` in `0.0..1.0` is how far we are
+    /// between the last update and the next one, for interpolation.
+    fn render(&mut self, window: &mut Window, alpha: f64);
+}
+
+pub struct GameLoop {
+    fixed_timestep: f64,
+    max_frame_time: f64,
+}
+
+impl Default for GameLoop {
+    fn default() -> Self {
+        GameLoop::new()
+    }
+}
+
+impl GameLoop {
+    pub fn new() -> GameLoop {
+        GameLoop {
+            fixed_timestep: 1.0 / 60.0,
+            max_frame_time: 0.25,
+        }
+    }
+
+    /// Has to be positive, `run` would never get out of its update loop
+    /// otherwise.
+    pub fn fixed_timestep(mut self, seconds: f64) -> Self {
+        assert!(
+            seconds.is_finite() && seconds > 0.0,
+            "fixed timestep has to be positive and finite, got {}",
+            seconds
+        );
+        self.fixed_timestep = seconds;
+        self
+    }
+
+    /// Longest frame we try to catch up on. Anything above is dropped so a
+    /// slow frame can't snowball into more and more updates per frame.
+    pub fn max_frame_time(mut self, seconds: f64) -> Self {
+        self.max_frame_time = seconds;
+        self
+    }
+
+    pub fn run<A: App>(&self, window: &mut Window, app: &mut A) {
+        let mut accumulator = 0.0;
+
+        while !window.should_close() {
+            window.pump_events();
+            accumulator += window.time().delta().min(self.max_frame_time);
+
+            while accumulator >= self.fixed_timestep {
+                app.update(window, self.fixed_timestep);
+                accumulator -= self.fixed_timestep;
+
+                // Every press/release is seen by exactly one update.
+                window.consume_input();
+            }
+
+            app.render(window, accumulator / self.fixed_timestep);
+            window.present();
+        }
+    }
+}
+
+pub fn run<A: App>(window: &mut Window, app: &mut A) {
+    GameLoop::new().run(window, app)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[should_panic(expected = "fixed timestep")]
+    fn zero_fixed_timestep() {
+        GameLoop::new().fixed_timestep(0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "fixed timestep")]
+    fn nan_fixed_timestep() {
+        GameLoop::new().fixed_timestep(f64::NAN);
+    }
+}
@@ -0,0 +1,77 @@
+use std::path::Path;
+
+use crate::errors::Errors;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CursorMode {
+    Normal,
+    /// Invisible while over the window, but otherwise free to move.
+    Hidden,
+    /// Hidden and locked to the window, with unaccelerated raw motion where
+    /// the platform supports it. Meant for mouse look.
+    Captured,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StandardCursor {
+    Arrow,
+    IBeam,
+    Crosshair,
+    Hand,
+    HorizontalResize,
+    VerticalResize,
+}
+
+pub struct Cursor {
+    handle: glfw::Cursor,
+}
+
+impl Cursor {
+    pub fn standard(shape: StandardCursor) -> Cursor {
+        let shape = match shape {
+            StandardCursor::Arrow => glfw::StandardCursor::Arrow,
+            StandardCursor::IBeam => glfw::StandardCursor::IBeam,
+            StandardCursor::Crosshair => glfw::StandardCursor::Crosshair,
+            StandardCursor::Hand => glfw::StandardCursor::Hand,
+            StandardCursor::HorizontalResize => glfw::StandardCursor::HResize,
+            StandardCursor::VerticalResize => glfw::StandardCursor::VResize,
+        };
+
+        Cursor {
+            handle: glfw::Cursor::standard(shape),
+        }
+    }
+
+    /// Loads a cursor from an image file, `hotspot` is the clicking point in
+    /// pixels from the top left corner.
+    pub fn from_file(source: impl AsRef<Path>, hotspot: (u32, u32)) -> Result<Cursor, Errors> {
+        let image = image::open(source)?;
+
+        Ok(Cursor::from_image(&image.into_rgba8(), hotspot))
+    }
+
+    pub fn from_image(image: &image::RgbaImage, hotspot: (u32, u32)) -> Cursor {
+        let pixels = image
+            .pixels()
+            .map(|pixel| u32::from_ne_bytes(pixel.0))
+            .collect();
+
+        let image = glfw::PixelImage {
+            width: image.width(),
+            height: image.height(),
+            pixels,
+        };
+
+        Cursor {
+            handle: glfw::Cursor::create_from_pixels(image, hotspot.0, hotspot.1),
+        }
+    }
+
+    pub(crate) fn into_handle(self) -> glfw::Cursor {
+        self.handle
+    }
+
+    pub(crate) fn from_handle(handle: glfw::Cursor) -> Cursor {
+        Cursor { handle }
+    }
+}
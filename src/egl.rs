@@ -0,0 +1,248 @@
+//! GL contexts without any display, through Mesa's EGL surfaceless platform.
+//! GLFW needs an X11 or Wayland connection before it tries any context API,
+//! so this is what's left on a CI machine with nothing but Mesa installed.
+//! libEGL is loaded at runtime, the engine doesn't link against it.
+
+use std::{
+    ffi::{c_char, c_void, CStr, CString},
+    mem, ptr,
+};
+
+use crate::errors::Errors;
+use crate::graphics::context::GlContext;
+
+type EglDisplay = *mut c_void;
+type EglConfig = *mut c_void;
+type EglContextHandle = *mut c_void;
+type EglInt = i32;
+type EglBoolean = u32;
+type EglEnum = u32;
+
+const EGL_FALSE: EglBoolean = 0;
+const EGL_NONE: EglInt = 0x3038;
+const EGL_SURFACE_TYPE: EglInt = 0x3033;
+const EGL_PBUFFER_BIT: EglInt = 0x0001;
+const EGL_RENDERABLE_TYPE: EglInt = 0x3040;
+const EGL_OPENGL_BIT: EglInt = 0x0008;
+const EGL_OPENGL_API: EglEnum = 0x30A2;
+const EGL_CONTEXT_MAJOR_VERSION: EglInt = 0x3098;
+const EGL_CONTEXT_MINOR_VERSION: EglInt = 0x30FB;
+const EGL_CONTEXT_OPENGL_PROFILE_MASK: EglInt = 0x30FD;
+const EGL_CONTEXT_OPENGL_CORE_PROFILE_BIT: EglInt = 0x0001;
+const EGL_PLATFORM_SURFACELESS_MESA: EglEnum = 0x31DD;
+
+type GetProcAddress = unsafe extern "C" fn(*const c_char) -> *const c_void;
+type GetPlatformDisplay = unsafe extern "C" fn(EglEnum, *mut c_void, *const EglInt) -> EglDisplay;
+type Initialize = unsafe extern "C" fn(EglDisplay, *mut EglInt, *mut EglInt) -> EglBoolean;
+type Terminate = unsafe extern "C" fn(EglDisplay) -> EglBoolean;
+type BindApi = unsafe extern "C" fn(EglEnum) -> EglBoolean;
+type ChooseConfig = unsafe extern "C" fn(
+    EglDisplay,
+    *const EglInt,
+    *mut EglConfig,
+    EglInt,
+    *mut EglInt,
+) -> EglBoolean;
+type CreateContext = unsafe extern "C" fn(
+    EglDisplay,
+    EglConfig,
+    EglContextHandle,
+    *const EglInt,
+) -> EglContextHandle;
+type DestroyContext = unsafe extern "C" fn(EglDisplay, EglContextHandle) -> EglBoolean;
+type MakeCurrent =
+    unsafe extern "C" fn(EglDisplay, *mut c_void, *mut c_void, EglContextHandle) -> EglBoolean;
+type GetError = unsafe extern "C" fn() -> EglInt;
+type GetCurrentContext = unsafe extern "C" fn() -> EglContextHandle;
+
+/// The entry points used, resolved once from libEGL.
+struct Egl {
+    get_proc_address: GetProcAddress,
+    terminate: Terminate,
+    destroy_context: DestroyContext,
+    make_current: MakeCurrent,
+    get_error: GetError,
+    _library: Library,
+}
+
+struct Library(*mut c_void);
+
+/// A current GL context with no default framebuffer, render into an FBO.
+pub(crate) struct EglContext {
+    egl: Egl,
+    display: EglDisplay,
+    context: EglContextHandle,
+    gl_context: GlContext,
+}
+
+impl EglContext {
+    /// Creates a `major.minor` core profile context and makes it current.
+    pub(crate) fn new(major: i32, minor: i32) -> Result<EglContext, Errors> {
+        unsafe {
+            let library = libc::dlopen(c"libEGL.so.1".as_ptr(), libc::RTLD_NOW | libc::RTLD_LOCAL);
+            if library.is_null() {
+                return Err(Errors::EglUnavailable);
+            }
+            let library = Library(library);
+
+            let initialize: Initialize = library.symbol(c"eglInitialize")?;
+            let bind_api: BindApi = library.symbol(c"eglBindAPI")?;
+            let choose_config: ChooseConfig = library.symbol(c"eglChooseConfig")?;
+            let create_context: CreateContext = library.symbol(c"eglCreateContext")?;
+            let get_current_context: GetCurrentContext = library.symbol(c"eglGetCurrentContext")?;
+            let egl = Egl {
+                get_proc_address: library.symbol(c"eglGetProcAddress")?,
+                terminate: library.symbol(c"eglTerminate")?,
+                destroy_context: library.symbol(c"eglDestroyContext")?,
+                make_current: library.symbol(c"eglMakeCurrent")?,
+                get_error: library.symbol(c"eglGetError")?,
+                _library: library,
+            };
+
+            let get_platform_display = (egl.get_proc_address)(c"eglGetPlatformDisplayEXT".as_ptr());
+            if get_platform_display.is_null() {
+                return Err(Errors::EglUnavailable);
+            }
+            let get_platform_display: GetPlatformDisplay = mem::transmute(get_platform_display);
+
+            let display =
+                get_platform_display(EGL_PLATFORM_SURFACELESS_MESA, ptr::null_mut(), ptr::null());
+            if display.is_null() {
+                return Err(egl.error("eglGetPlatformDisplayEXT"));
+            }
+            if initialize(display, ptr::null_mut(), ptr::null_mut()) == EGL_FALSE {
+                return Err(egl.error("eglInitialize"));
+            }
+
+            // Terminates the display again on the way out.
+            let fail = |call| {
+                let error = egl.error(call);
+                (egl.terminate)(display);
+                error
+            };
+
+            if bind_api(EGL_OPENGL_API) == EGL_FALSE {
+                return Err(fail("eglBindAPI"));
+            }
+
+            // Surfaceless only has pbuffer configs, the default asks for window ones.
+            let config_attributes = [
+                EGL_SURFACE_TYPE,
+                EGL_PBUFFER_BIT,
+                EGL_RENDERABLE_TYPE,
+                EGL_OPENGL_BIT,
+                EGL_NONE,
+            ];
+            let mut config = ptr::null_mut();
+            let mut config_count = 0;
+            if choose_config(
+                display,
+                config_attributes.as_ptr(),
+                &mut config,
+                1,
+                &mut config_count,
+            ) == EGL_FALSE
+                || config_count == 0
+            {
+                // Not finding a config isn't an EGL error, it's reported as success.
+                return Err(fail("eglChooseConfig"));
+            }
+
+            let context_attributes = [
+                EGL_CONTEXT_MAJOR_VERSION,
+                major,
+                EGL_CONTEXT_MINOR_VERSION,
+                minor,
+                EGL_CONTEXT_OPENGL_PROFILE_MASK,
+                EGL_CONTEXT_OPENGL_CORE_PROFILE_BIT,
+                EGL_NONE,
+            ];
+            let handle = create_context(
+                display,
+                config,
+                ptr::null_mut(),
+                context_attributes.as_ptr(),
+            );
+            if handle.is_null() {
+                return Err(fail("eglCreateContext"));
+            }
+
+            // Owns the display and context from here, cleaning up on drop.
+            let context = EglContext {
+                egl,
+                display,
+                context: handle,
+                gl_context: GlContext::new(move || get_current_context() == handle),
+            };
+
+            if !context.make_current() {
+                return Err(context.egl.error("eglMakeCurrent"));
+            }
+
+            let get_proc_address = context.egl.get_proc_address;
+            gl::load_with(|name| match CString::new(name) {
+                Ok(name) => get_proc_address(name.as_ptr()),
+                Err(_) => ptr::null(),
+            });
+
+            Ok(context)
+        }
+    }
+
+    pub(crate) fn context(&self) -> GlContext {
+        self.gl_context.clone()
+    }
+
+    pub(crate) fn make_current(&self) -> bool {
+        unsafe {
+            (self.egl.make_current)(self.display, ptr::null_mut(), ptr::null_mut(), self.context)
+                != EGL_FALSE
+        }
+    }
+}
+
+impl Egl {
+    fn error(&self, call: &'static str) -> Errors {
+        Errors::EglFailed {
+            call,
+            error: unsafe { (self.get_error)() },
+        }
+    }
+}
+
+impl Drop for EglContext {
+    fn drop(&mut self) {
+        self.gl_context.invalidate();
+
+        unsafe {
+            (self.egl.make_current)(
+                self.display,
+                ptr::null_mut(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+            );
+            (self.egl.destroy_context)(self.display, self.context);
+            (self.egl.terminate)(self.display);
+        }
+    }
+}
+
+impl Library {
+    /// `F` has to be the function pointer type of `name`.
+    unsafe fn symbol<F: Copy>(&self, name: &CStr) -> Result<F, Errors> {
+        let address = libc::dlsym(self.0, name.as_ptr());
+        if address.is_null() {
+            return Err(Errors::EglUnavailable);
+        }
+
+        Ok(mem::transmute_copy(&address))
+    }
+}
+
+impl Drop for Library {
+    fn drop(&mut self) {
+        unsafe {
+            libc::dlclose(self.0);
+        }
+    }
+}
@@ -1,15 +1,95 @@
+use std::path::PathBuf;
+
 use thiserror::Error;
 
+use crate::graphics::diagnostics::{self, Diagnostic};
+use crate::graphics::shaders::ShaderStage;
+use crate::monitor::VideoMode;
+use crate::window::WindowMode;
+
 #[derive(Error, Debug)]
 pub enum Errors {
     #[error("This function was not implemented")]
     NotImplemented,
-    #[error("")]
-    ShadersError(ShaderErrors)
+    #[error("{0}")]
+    ShadersError(#[from] ShaderErrors),
+    #[error("{0}")]
+    ImageError(#[from] image::ImageError),
+    #[error("{0}")]
+    WindowError(#[from] WindowErrors),
+    #[error("{0}")]
+    InputError(#[from] InputErrors),
+    #[error("Framebuffer is incomplete, status {0:#x}")]
+    FramebufferIncomplete(gl::types::GLenum),
+    #[error("Failed to map buffer")]
+    BufferMapFailed,
+    #[error("libEGL with the surfaceless platform is not available")]
+    EglUnavailable,
+    #[error("{call} failed with EGL error {error:#x}")]
+    EglFailed { call: &'static str, error: i32 },
 }
 
 #[derive(Error, Debug)]
 pub enum ShaderErrors {
-    #[error("")]
-    GlError(gl::types::GLenum)
-}
\ No newline at end of file
+    #[error("Failed to read shader {}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Shader {} contains a nul byte", .0.display())]
+    NulInSource(PathBuf),
+    #[error("{}:{line}: could not find include {include:?}", path.display())]
+    IncludeNotFound {
+        path: PathBuf,
+        line: u32,
+        include: String,
+    },
+    #[error("{}:{line}: malformed include {include}", path.display())]
+    InvalidInclude {
+        path: PathBuf,
+        line: u32,
+        include: String,
+    },
+    #[error("Shader {} includes itself", .0.display())]
+    IncludeCycle(PathBuf),
+    #[error("Failed to compile {stage} shader {}:\n{}", path.display(), diagnostics::render(diagnostics))]
+    Compile {
+        stage: ShaderStage,
+        path: PathBuf,
+        log: String,
+        diagnostics: Vec<Diagnostic>,
+    },
+    #[error("Failed to link shader program:\n{log}")]
+    Link { log: String },
+}
+
+#[derive(Error, Debug)]
+pub enum WindowErrors {
+    #[error("Failed to initialize glfw: {0}")]
+    InitFailed(glfw::InitError),
+    #[error("Failed to create glfw window")]
+    CreationFailed,
+    #[error("Window mode {0:?} is not supported, no monitor available")]
+    UnsupportedMode(WindowMode),
+    #[error("No monitor with index {0}")]
+    MonitorNotFound(usize),
+    #[error("Video mode {}x{}@{}Hz is not supported by the monitor", .0.width, .0.height, .0.refresh_rate)]
+    UnsupportedVideoMode(VideoMode),
+    #[error("Requested an OpenGL {}.{} context but got {}.{}", requested.0, requested.1, actual.0, actual.1)]
+    ContextVersionMismatch {
+        requested: (u32, u32),
+        actual: (u32, u32),
+    },
+}
+
+#[derive(Error, Debug)]
+pub enum InputErrors {
+    #[error("Failed to access input bindings: {0}")]
+    Io(std::io::Error),
+    #[error("Failed to parse input bindings: {0}")]
+    Deserialize(toml::de::Error),
+    #[error("Failed to serialize input bindings: {0}")]
+    Serialize(toml::ser::Error),
+    #[error("Failed to parse gamepad mappings")]
+    InvalidGamepadMappings,
+}
@@ -0,0 +1,314 @@
+use std::{marker::PhantomData, mem, ops::Range, os::raw::c_void, ptr, slice};
+
+use gl::types::{GLbitfield, GLenum, GLintptr, GLsizeiptr};
+
+pub use bytemuck::{Pod, Zeroable};
+
+use crate::errors::Errors;
+use crate::logger;
+
+use super::context::GlContext;
+use super::gl_wrapper::BufferObject;
+
+/// A GL buffer holding `len` elements of `T`. `T: Pod` is what makes it
+/// sound to hand the bytes to GL and to read mapped memory back as `T`s.
+pub struct Buffer<T: Pod> {
+    object: BufferObject,
+    len: usize,
+    _element: PhantomData<T>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MapAccess {
+    Read,
+    Write,
+    ReadWrite,
+    /// Write only, the previous contents of the range are thrown away so the
+    /// driver doesn't have to wait for draws still reading them.
+    WriteDiscard,
+}
+
+impl<T: Pod> Buffer<T> {
+    pub fn new(context: &GlContext, target: GLenum, usage: GLenum) -> Buffer<T> {
+        Buffer {
+            object: BufferObject::new(context, target, usage),
+            len: 0,
+            _element: PhantomData,
+        }
+    }
+
+    pub fn with_data(context: &GlContext, target: GLenum, data: &[T], usage: GLenum) -> Buffer<T> {
+        let mut buffer = Buffer::new(context, target, usage);
+        buffer.set_data(data);
+        buffer
+    }
+
+    /// Reserves room for `len` elements, the contents are undefined.
+    pub fn allocate(&mut self, len: usize) {
+        self.bind();
+        unsafe {
+            gl::BufferData(
+                self.object.target(),
+                (len * mem::size_of::<T>()) as GLsizeiptr,
+                ptr::null(),
+                self.object.usage(),
+            );
+        }
+        self.len = len;
+    }
+
+    /// Replaces the contents, reallocating the storage to fit `data`.
+    pub fn set_data(&mut self, data: &[T]) {
+        self.bind();
+        self.object.store_data(data);
+        self.len = data.len();
+    }
+
+    /// Overwrites the elements starting at `offset` without reallocating.
+    pub fn update(&self, offset: usize, data: &[T]) {
+        assert!(
+            offset + data.len() <= self.len,
+            "update of {}..{} out of range for a buffer of {} elements",
+            offset,
+            offset + data.len(),
+            self.len
+        );
+
+        if data.is_empty() {
+            return;
+        }
+
+        self.bind();
+        unsafe {
+            gl::BufferSubData(
+                self.object.target(),
+                (offset * mem::size_of::<T>()) as GLintptr,
+                mem::size_of_val(data) as GLsizeiptr,
+                data.as_ptr() as *const c_void,
+            );
+        }
+    }
+
+    /// Gives the buffer fresh storage of the same size, so writing new data
+    /// doesn't stall on draws still using the old one.
+    pub fn orphan(&mut self) {
+        self.allocate(self.len);
+    }
+
+    /// Orphans and uploads, growing the storage if `data` doesn't fit. The
+    /// usual way to stream per frame data.
+    pub fn stream(&mut self, data: &[T]) {
+        if data.len() > self.len {
+            self.set_data(data);
+            return;
+        }
+
+        self.orphan();
+        self.len = data.len();
+        self.update(0, data);
+    }
+
+    /// Maps `range` of elements into client memory until the returned guard
+    /// is dropped.
+    pub fn map(
+        &mut self,
+        range: Range<usize>,
+        access: MapAccess,
+    ) -> Result<MappedBuffer<'_, T>, Errors> {
+        assert!(
+            range.start <= range.end && range.end <= self.len,
+            "map of {:?} out of range for a buffer of {} elements",
+            range,
+            self.len
+        );
+
+        let flags: GLbitfield = match access {
+            MapAccess::Read => gl::MAP_READ_BIT,
+            MapAccess::Write => gl::MAP_WRITE_BIT,
+            MapAccess::ReadWrite => gl::MAP_READ_BIT | gl::MAP_WRITE_BIT,
+            MapAccess::WriteDiscard => gl::MAP_WRITE_BIT | gl::MAP_INVALIDATE_RANGE_BIT,
+        };
+
+        let len = range.end - range.start;
+        if len == 0 {
+            return Ok(MappedBuffer {
+                buffer: self,
+                data: ptr::NonNull::dangling().as_ptr(),
+                len: 0,
+                access,
+                mapped: false,
+            });
+        }
+
+        self.bind();
+        let data = unsafe {
+            gl::MapBufferRange(
+                self.object.target(),
+                (range.start * mem::size_of::<T>()) as GLintptr,
+                (len * mem::size_of::<T>()) as GLsizeiptr,
+                flags,
+            )
+        } as *mut T;
+
+        if data.is_null() {
+            return Err(Errors::BufferMapFailed);
+        }
+
+        Ok(MappedBuffer {
+            buffer: self,
+            data,
+            len,
+            access,
+            mapped: true,
+        })
+    }
+
+    pub fn bind(&self) {
+        self.object.bind();
+    }
+
+    pub fn unbind(&self) {
+        self.object.unbind();
+    }
+
+    pub fn id(&self) -> gl::types::GLuint {
+        self.object.id()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn size_in_bytes(&self) -> usize {
+        self.len * mem::size_of::<T>()
+    }
+
+    pub(crate) fn into_object(self) -> BufferObject {
+        self.object
+    }
+}
+
+/// Integer types GL accepts as indices.
+pub trait Index: Pod {
+    const TYPE: GLenum;
+}
+
+impl Index for u8 {
+    const TYPE: GLenum = gl::UNSIGNED_BYTE;
+}
+
+impl Index for u16 {
+    const TYPE: GLenum = gl::UNSIGNED_SHORT;
+}
+
+impl Index for u32 {
+    const TYPE: GLenum = gl::UNSIGNED_INT;
+}
+
+/// Element array buffer, `I` picks between `u8`, `u16` and `u32` indices.
+/// It binds to whichever `Vao` is bound at the time, so bind the one it
+/// belongs to first.
+pub struct IndexBuffer<I: Index> {
+    buffer: Buffer<I>,
+}
+
+impl<I: Index> IndexBuffer<I> {
+    pub fn new(context: &GlContext, indices: &[I], usage: GLenum) -> IndexBuffer<I> {
+        IndexBuffer {
+            buffer: Buffer::with_data(context, gl::ELEMENT_ARRAY_BUFFER, indices, usage),
+        }
+    }
+
+    pub fn index_type(&self) -> GLenum {
+        I::TYPE
+    }
+
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    pub fn buffer(&self) -> &Buffer<I> {
+        &self.buffer
+    }
+
+    /// For updates, mapping and streaming, the target stays
+    /// `ELEMENT_ARRAY_BUFFER`.
+    pub fn buffer_mut(&mut self) -> &mut Buffer<I> {
+        &mut self.buffer
+    }
+
+    pub fn into_buffer(self) -> Buffer<I> {
+        self.buffer
+    }
+}
+
+/// A mapped range of a `Buffer`, unmapped on drop. It can only be read
+/// through when mapped with `Read` or `ReadWrite`, a write mapping starts
+/// out with undefined contents, and only written when mapped with anything
+/// but `Read`.
+pub struct MappedBuffer<'a, T: Pod> {
+    buffer: &'a mut Buffer<T>,
+    data: *mut T,
+    len: usize,
+    access: MapAccess,
+    mapped: bool,
+}
+
+impl<T: Pod> MappedBuffer<'_, T> {
+    /// Mapped element count, available without reading through the mapping.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn access(&self) -> MapAccess {
+        self.access
+    }
+
+    /// The mapped elements, `None` for a write only mapping.
+    pub fn as_slice(&self) -> Option<&[T]> {
+        match self.access {
+            MapAccess::Read | MapAccess::ReadWrite => {
+                Some(unsafe { slice::from_raw_parts(self.data, self.len) })
+            }
+            MapAccess::Write | MapAccess::WriteDiscard => None,
+        }
+    }
+
+    /// The mapped elements to write to, `None` for a read only mapping.
+    pub fn as_mut_slice(&mut self) -> Option<&mut [T]> {
+        match self.access {
+            MapAccess::Write | MapAccess::ReadWrite | MapAccess::WriteDiscard => {
+                Some(unsafe { slice::from_raw_parts_mut(self.data, self.len) })
+            }
+            MapAccess::Read => None,
+        }
+    }
+}
+
+impl<T: Pod> Drop for MappedBuffer<'_, T> {
+    fn drop(&mut self) {
+        // Destroying the context unmapped it already, and while another one
+        // is current there's nothing to unmap it with.
+        if !self.mapped || !self.buffer.object.context().is_current() {
+            return;
+        }
+
+        self.buffer.bind();
+        let intact = unsafe { gl::UnmapBuffer(self.buffer.object.target()) };
+        if intact == gl::FALSE {
+            logger::warn!("Buffer {} was corrupted while mapped", self.buffer.id());
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use std::{cell::Cell, ffi::CStr, rc::Rc};
+
+use crate::logger;
+
+/// Handle to a GL context, handed out by `Window::init_gl` and
+/// `HeadlessContext`. Every GL object takes one on creation and keeps a
+/// clone, which makes them `!Send`. Using an object panics unless its
+/// context is alive and current on this thread. Once the context is gone
+/// their `Drop` skips the delete.
+#[derive(Clone)]
+pub struct GlContext {
+    inner: Rc<Inner>,
+}
+
+struct Inner {
+    alive: Cell<bool>,
+    /// Asks the windowing api whether this context is the current one.
+    is_current: Box<dyn Fn() -> bool>,
+    /// Looked up on first use, the context has to be current for that.
+    program_uniforms: Cell<Option<bool>>,
+}
+
+impl GlContext {
+    pub(crate) fn new(is_current: impl Fn() -> bool + 'static) -> GlContext {
+        GlContext {
+            inner: Rc::new(Inner {
+                alive: Cell::new(true),
+                is_current: Box::new(is_current),
+                program_uniforms: Cell::new(None),
+            }),
+        }
+    }
+
+    pub(crate) fn invalidate(&self) {
+        self.inner.alive.set(false);
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.inner.alive.get()
+    }
+
+    /// Whether GL calls made now would go to this context.
+    pub fn is_current(&self) -> bool {
+        self.is_alive() && (self.inner.is_current)()
+    }
+
+    /// Called by GL objects before issuing commands, which would go nowhere
+    /// once the context is destroyed and to the wrong objects while another
+    /// context is current.
+    #[track_caller]
+    pub(crate) fn assert_current(&self) {
+        assert!(
+            self.is_alive(),
+            "GL object used after its context was destroyed"
+        );
+        assert!(
+            (self.inner.is_current)(),
+            "GL object used while another context is current"
+        );
+    }
+
+    /// Whether `glProgramUniform*` can be used, which sets uniforms without
+    /// binding the program. Core in GL 4.1, `ARB_separate_shader_objects`
+    /// before that.
+    pub(crate) fn has_program_uniforms(&self) -> bool {
+        if let Some(supported) = self.inner.program_uniforms.get() {
+            return supported;
+        }
+
+        self.assert_current();
+        let supported = unsafe {
+            let (mut major, mut minor) = (0, 0);
+            gl::GetIntegerv(gl::MAJOR_VERSION, &mut major);
+            gl::GetIntegerv(gl::MINOR_VERSION, &mut minor);
+
+            (major, minor) >= (4, 1) || has_extension("GL_ARB_separate_shader_objects")
+        };
+        self.inner.program_uniforms.set(Some(supported));
+
+        supported
+    }
+
+    /// Runs `delete` for a dropped object if its context is current. A
+    /// destroyed context already freed it, and deleting while another
+    /// context is current would free whatever has the same name there, so
+    /// that case leaks instead.
+    pub(crate) fn release(&self, kind: &str, id: gl::types::GLuint, delete: impl FnOnce()) {
+        if self.is_current() {
+            delete();
+        } else if self.is_alive() {
+            logger::warn!(
+                "Leaking {} {}, it was dropped while another context was current",
+                kind,
+                id
+            );
+        }
+    }
+}
+
+unsafe fn has_extension(name: &str) -> bool {
+    let mut count = 0;
+    gl::GetIntegerv(gl::NUM_EXTENSIONS, &mut count);
+
+    (0..count.max(0) as gl::types::GLuint).any(|index| {
+        let extension = gl::GetStringi(gl::EXTENSIONS, index);
+        !extension.is_null() && CStr::from_ptr(extension as *const _).to_bytes() == name.as_bytes()
+    })
+}
@@ -0,0 +1,383 @@
+//! Parses shader compiler info logs into `Diagnostic`s. Drivers agree on
+//! nothing, the formats understood are
+//!
+//! - Mesa: `0:12(5): error: message`, `preprocessor error` for glcpp
+//! - NVIDIA: `0(12) : error C1008: message`
+//! - AMD, Intel and Apple: `ERROR: 0:12: message`
+//!
+//! where `0` is the GLSL source string number set by `#line`. Lines in none
+//! of these formats are kept as diagnostics without a location.
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Info => write!(f, "info"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// File the diagnostic points at, when the source string is known.
+    pub path: Option<PathBuf>,
+    /// 1-based, like the drivers report them.
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    pub message: String,
+    /// The offending line and its neighbours, as (line number, text).
+    pub context: Vec<(u32, String)>,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}: {}", self.severity, self.message)?;
+
+        let path = self.path.as_deref().unwrap_or(Path::new("<unknown>"));
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => {
+                writeln!(f, " --> {}:{}:{}", path.display(), line, column)?
+            }
+            (Some(line), None) => writeln!(f, " --> {}:{}", path.display(), line)?,
+            _ => return Ok(()),
+        }
+
+        let width = self
+            .context
+            .iter()
+            .map(|(number, _)| number.to_string().len())
+            .max()
+            .unwrap_or(0);
+
+        writeln!(f, "{:width$} |", "")?;
+        for (number, text) in &self.context {
+            writeln!(f, "{:>width$} | {}", number, text)?;
+
+            if Some(*number) == self.line {
+                if let Some(column) = self.column {
+                    let offset = (column as usize).saturating_sub(1);
+                    writeln!(f, "{:width$} | {:offset$}^", "", "")?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The files a shader was assembled from, indexed by GLSL source string
+/// number.
+#[derive(Clone, Debug, Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SourceFile {
+    pub path: PathBuf,
+    pub source: String,
+}
+
+impl SourceMap {
+    pub fn new() -> SourceMap {
+        SourceMap::default()
+    }
+
+    /// Registers a file and returns its source string number.
+    pub fn add(&mut self, path: impl Into<PathBuf>, source: impl Into<String>) -> u32 {
+        self.files.push(SourceFile {
+            path: path.into(),
+            source: source.into(),
+        });
+        self.files.len() as u32 - 1
+    }
+
+    pub fn get(&self, index: u32) -> Option<&SourceFile> {
+        self.files.get(index as usize)
+    }
+
+    pub fn files(&self) -> &[SourceFile] {
+        &self.files
+    }
+}
+
+/// Lines shown around the offending one.
+const CONTEXT_LINES: u32 = 1;
+
+pub fn parse_info_log(log: &str, sources: &SourceMap) -> Vec<Diagnostic> {
+    log.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let (severity, source, line_number, column, message) =
+                parse_line(line).unwrap_or_else(|| (guess_severity(line), None, None, None, line));
+
+            let file = source.and_then(|source| sources.get(source));
+            let context = match (file, line_number) {
+                (Some(file), Some(line)) => source_context(&file.source, line),
+                _ => Vec::new(),
+            };
+
+            Diagnostic {
+                severity,
+                path: file.map(|file| file.path.clone()),
+                line: line_number,
+                column,
+                message: message.trim().to_owned(),
+                context,
+            }
+        })
+        .collect()
+}
+
+/// All diagnostics one after the other, for logs and error messages.
+pub fn render(diagnostics: &[Diagnostic]) -> String {
+    diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+type ParsedLine<'a> = (Severity, Option<u32>, Option<u32>, Option<u32>, &'a str);
+
+fn parse_line(line: &str) -> Option<ParsedLine<'_>> {
+    parse_prefixed(line)
+        .or_else(|| parse_mesa(line))
+        .or_else(|| parse_nvidia(line))
+}
+
+/// `ERROR: 0:12: message`
+fn parse_prefixed(line: &str) -> Option<ParsedLine<'_>> {
+    let (severity, rest) = line.split_once(':')?;
+    let severity = parse_severity(severity)?;
+
+    let location = rest
+        .trim_start()
+        .split_once(':')
+        .and_then(|(source, rest)| {
+            let (line, message) = rest.split_once(':')?;
+            Some((source.parse().ok()?, line.trim().parse().ok()?, message))
+        });
+
+    match location {
+        Some((source, line, message)) => Some((severity, Some(source), Some(line), None, message)),
+        None => Some((severity, None, None, None, rest)),
+    }
+}
+
+/// `0:12(5): error: message`
+fn parse_mesa(line: &str) -> Option<ParsedLine<'_>> {
+    let (source, rest) = line.split_once(':')?;
+    let (line_number, rest) = rest.split_once('(')?;
+    let (column, rest) = rest.split_once(')')?;
+    let (severity, message) = rest.strip_prefix(':')?.split_once(':')?;
+
+    Some((
+        parse_severity(severity)?,
+        Some(source.trim().parse().ok()?),
+        Some(line_number.parse().ok()?),
+        Some(column.parse().ok()?),
+        message,
+    ))
+}
+
+/// `0(12) : error C1008: message`
+fn parse_nvidia(line: &str) -> Option<ParsedLine<'_>> {
+    let (source, rest) = line.split_once('(')?;
+    let (line_number, rest) = rest.split_once(')')?;
+    let rest = rest.trim_start().strip_prefix(':')?.trim_start();
+
+    // Keep the error code, it's the only thing worth searching for.
+    let severity_end = rest.find(|c: char| !c.is_ascii_alphabetic())?;
+    let severity = parse_severity(&rest[..severity_end])?;
+
+    Some((
+        severity,
+        Some(source.trim().parse().ok()?),
+        Some(line_number.parse().ok()?),
+        None,
+        rest[severity_end..].trim_start(),
+    ))
+}
+
+fn parse_severity(text: &str) -> Option<Severity> {
+    match text.trim().to_ascii_lowercase().as_str() {
+        "error" | "fatal error" | "preprocessor error" => Some(Severity::Error),
+        "warning" => Some(Severity::Warning),
+        "info" | "note" => Some(Severity::Info),
+        _ => None,
+    }
+}
+
+fn guess_severity(line: &str) -> Severity {
+    let line = line.to_ascii_lowercase();
+
+    if line.contains("error") {
+        Severity::Error
+    } else if line.contains("warning") {
+        Severity::Warning
+    } else {
+        Severity::Info
+    }
+}
+
+fn source_context(source: &str, line: u32) -> Vec<(u32, String)> {
+    let first = line.saturating_sub(CONTEXT_LINES).max(1);
+
+    source
+        .lines()
+        .enumerate()
+        .map(|(index, text)| (index as u32 + 1, text))
+        .skip(first as usize - 1)
+        .take_while(|(number, _)| *number <= line + CONTEXT_LINES)
+        .map(|(number, text)| (number, text.to_owned()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sources() -> SourceMap {
+        let mut sources = SourceMap::new();
+        sources.add(
+            "main.frag",
+            "#version 330 core\nvoid main() {\n    oops\n}\n",
+        );
+        sources.add("common.glsl", "float a;\nfloat b\nfloat c;\n");
+        sources
+    }
+
+    fn parse_one(log: &str) -> Diagnostic {
+        let mut diagnostics = parse_info_log(log, &sources());
+        assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+        diagnostics.remove(0)
+    }
+
+    #[test]
+    fn mesa() {
+        let diagnostic = parse_one("0:3(5): error: `oops' undeclared\n");
+
+        assert_eq!(diagnostic.severity, Severity::Error);
+        assert_eq!(diagnostic.path.as_deref(), Some(Path::new("main.frag")));
+        assert_eq!((diagnostic.line, diagnostic.column), (Some(3), Some(5)));
+        assert_eq!(diagnostic.message, "`oops' undeclared");
+        assert_eq!(
+            diagnostic.context,
+            vec![
+                (2, "void main() {".to_owned()),
+                (3, "    oops".to_owned()),
+                (4, "}".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn mesa_preprocessor_error() {
+        let diagnostic = parse_one("0:1(10): preprocessor error: syntax error, unexpected NEWLINE");
+
+        assert_eq!(diagnostic.severity, Severity::Error);
+        assert_eq!(diagnostic.path.as_deref(), Some(Path::new("main.frag")));
+        assert_eq!((diagnostic.line, diagnostic.column), (Some(1), Some(10)));
+        assert_eq!(diagnostic.message, "syntax error, unexpected NEWLINE");
+    }
+
+    #[test]
+    fn mesa_warning() {
+        let diagnostic = parse_one("1:1(7): warning: `a' declared but never used");
+
+        assert_eq!(diagnostic.severity, Severity::Warning);
+        assert_eq!(diagnostic.path.as_deref(), Some(Path::new("common.glsl")));
+        assert_eq!((diagnostic.line, diagnostic.column), (Some(1), Some(7)));
+    }
+
+    #[test]
+    fn nvidia() {
+        let diagnostic = parse_one("1(2) : error C1008: undefined variable \"b\"");
+
+        assert_eq!(diagnostic.severity, Severity::Error);
+        assert_eq!(diagnostic.path.as_deref(), Some(Path::new("common.glsl")));
+        assert_eq!((diagnostic.line, diagnostic.column), (Some(2), None));
+        assert_eq!(diagnostic.message, "C1008: undefined variable \"b\"");
+        assert_eq!(diagnostic.context.len(), 3);
+    }
+
+    #[test]
+    fn amd_and_intel() {
+        let diagnostic = parse_one("ERROR: 0:3: 'oops' : undeclared identifier");
+
+        assert_eq!(diagnostic.severity, Severity::Error);
+        assert_eq!(diagnostic.path.as_deref(), Some(Path::new("main.frag")));
+        assert_eq!((diagnostic.line, diagnostic.column), (Some(3), None));
+        assert_eq!(diagnostic.message, "'oops' : undeclared identifier");
+
+        let diagnostic = parse_one("WARNING: 1:2: extension not supported");
+        assert_eq!(diagnostic.severity, Severity::Warning);
+        assert_eq!(diagnostic.path.as_deref(), Some(Path::new("common.glsl")));
+    }
+
+    #[test]
+    fn without_location() {
+        let diagnostic = parse_one("ERROR: 1 compilation errors.  No code generated.");
+        assert_eq!(diagnostic.severity, Severity::Error);
+        assert_eq!((diagnostic.path.as_ref(), diagnostic.line), (None, None));
+        assert_eq!(
+            diagnostic.message,
+            "1 compilation errors.  No code generated."
+        );
+
+        let diagnostic = parse_one("Something went wrong with a warning");
+        assert_eq!(diagnostic.severity, Severity::Warning);
+        assert_eq!(diagnostic.line, None);
+    }
+
+    #[test]
+    fn unknown_source_string() {
+        let diagnostic = parse_one("7:3(5): error: nope");
+
+        assert_eq!(diagnostic.path, None);
+        assert_eq!(diagnostic.line, Some(3));
+        assert!(diagnostic.context.is_empty());
+    }
+
+    #[test]
+    fn several_lines() {
+        let log = "0:3(5): error: first\n\n0:4(1): warning: second\n";
+        let diagnostics = parse_info_log(log, &sources());
+
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].message, "first");
+        assert_eq!(diagnostics[1].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn display_points_at_the_column() {
+        let rendered = parse_one("0:3(5): error: `oops' undeclared").to_string();
+
+        assert_eq!(
+            rendered,
+            "error: `oops' undeclared\n \
+             --> main.frag:3:5\n  \
+             |\n\
+             2 | void main() {\n\
+             3 |     oops\n  \
+             |     ^\n\
+             4 | }\n"
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use std::{path::Path, ptr};
+
+use image::RgbaImage;
+
+use crate::errors::Errors;
+
+use super::{context::GlContext, screenshot};
+
+/// Offscreen render target with an RGBA8 color texture and a
+/// depth/stencil renderbuffer.
+pub struct Framebuffer {
+    id: gl::types::GLuint,
+    color_texture: gl::types::GLuint,
+    depth_stencil: gl::types::GLuint,
+    width: i32,
+    height: i32,
+    context: GlContext,
+}
+
+impl Framebuffer {
+    pub fn new(context: &GlContext, width: i32, height: i32) -> Result<Framebuffer, Errors> {
+        context.assert_current();
+        let mut framebuffer = Framebuffer {
+            id: 0,
+            color_texture: 0,
+            depth_stencil: 0,
+            width,
+            height,
+            context: context.clone(),
+        };
+
+        let status = unsafe {
+            gl::GenFramebuffers(1, &mut framebuffer.id);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, framebuffer.id);
+
+            gl::GenTextures(1, &mut framebuffer.color_texture);
+            gl::BindTexture(gl::TEXTURE_2D, framebuffer.color_texture);
+            gl::TexImage2D(
+                gl::TEXTURE_2D,
+                0,
+                gl::RGBA8 as i32,
+                width,
+                height,
+                0,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                ptr::null(),
+            );
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+            gl::FramebufferTexture2D(
+                gl::FRAMEBUFFER,
+                gl::COLOR_ATTACHMENT0,
+                gl::TEXTURE_2D,
+                framebuffer.color_texture,
+                0,
+            );
+
+            gl::GenRenderbuffers(1, &mut framebuffer.depth_stencil);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, framebuffer.depth_stencil);
+            gl::RenderbufferStorage(gl::RENDERBUFFER, gl::DEPTH24_STENCIL8, width, height);
+            gl::FramebufferRenderbuffer(
+                gl::FRAMEBUFFER,
+                gl::DEPTH_STENCIL_ATTACHMENT,
+                gl::RENDERBUFFER,
+                framebuffer.depth_stencil,
+            );
+
+            let status = gl::CheckFramebufferStatus(gl::FRAMEBUFFER);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            status
+        };
+
+        if status != gl::FRAMEBUFFER_COMPLETE {
+            return Err(Errors::FramebufferIncomplete(status));
+        }
+
+        Ok(framebuffer)
+    }
+
+    pub fn id(&self) -> gl::types::GLuint {
+        self.id
+    }
+
+    /// The color attachment, to sample what was rendered.
+    pub fn color_texture(&self) -> gl::types::GLuint {
+        self.color_texture
+    }
+
+    /// Binds the framebuffer and sets the viewport to cover it.
+    pub fn bind(&self) {
+        self.context.assert_current();
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.id);
+            gl::Viewport(0, 0, self.width, self.height);
+        }
+    }
+
+    pub fn unbind(&self) {
+        self.context.assert_current();
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+    }
+
+    pub fn size(&self) -> (i32, i32) {
+        (self.width, self.height)
+    }
+
+    /// Tightly packed RGBA8 pixels, bottom row first as GL stores them.
+    pub fn read_pixels(&self) -> Vec<u8> {
+        self.context.assert_current();
+        screenshot::read_pixels(self.id, self.width, self.height)
+    }
+
+    /// The framebuffer contents as an upright image.
+    pub fn read_image(&self) -> RgbaImage {
+        self.context.assert_current();
+        screenshot::capture(self.id, self.width, self.height)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Errors> {
+        screenshot::save(&self.read_image(), path)
+    }
+}
+
+impl Drop for Framebuffer {
+    fn drop(&mut self) {
+        self.context.release("framebuffer", self.id, || unsafe {
+            gl::DeleteFramebuffers(1, &self.id);
+            gl::DeleteTextures(1, &self.color_texture);
+            gl::DeleteRenderbuffers(1, &self.depth_stencil);
+        });
+    }
+}
@@ -1,83 +1,138 @@
 use std::{mem, os::raw::c_void};
 
+use bytemuck::Pod;
+
+use super::context::GlContext;
 
 pub struct Vao {
-    pub id: gl::types::GLuint,
+    id: gl::types::GLuint,
+    context: GlContext,
 }
 
 impl Vao {
-    pub fn new() -> Vao {
+    pub fn new(context: &GlContext) -> Vao {
+        context.assert_current();
         let mut id = 0;
         unsafe {
             gl::GenVertexArrays(1, &mut id);
         }
 
         Vao {
-            id
+            id,
+            context: context.clone(),
         }
     }
 
+    pub fn id(&self) -> gl::types::GLuint {
+        self.id
+    }
+
     pub fn bind(&self) {
+        self.context.assert_current();
         unsafe {
             gl::BindVertexArray(self.id);
         }
     }
 
     pub fn unbind(&self) {
+        self.context.assert_current();
         unsafe {
             gl::BindVertexArray(0);
         }
     }
 }
 
+impl Drop for Vao {
+    fn drop(&mut self) {
+        self.context.release("vertex array", self.id, || unsafe {
+            gl::DeleteVertexArrays(1, &self.id);
+        });
+    }
+}
+
 pub struct BufferObject {
-    pub id: gl::types::GLuint,
+    id: gl::types::GLuint,
     r#type: gl::types::GLenum,
     usage: gl::types::GLenum,
+    context: GlContext,
 }
 
 impl BufferObject {
-    pub fn new(r#type: gl::types::GLenum, usage: gl::types::GLenum) -> BufferObject {
+    pub fn new(
+        context: &GlContext,
+        r#type: gl::types::GLenum,
+        usage: gl::types::GLenum,
+    ) -> BufferObject {
+        context.assert_current();
         let mut id = 0;
         unsafe {
             gl::GenBuffers(1, &mut id);
         }
 
-        BufferObject { id, r#type, usage }
+        BufferObject {
+            id,
+            r#type,
+            usage,
+            context: context.clone(),
+        }
+    }
+
+    pub fn id(&self) -> gl::types::GLuint {
+        self.id
     }
 
     pub fn bind(&self) {
+        self.context.assert_current();
         unsafe {
             gl::BindBuffer(self.r#type, self.id);
         }
     }
 
     pub fn unbind(&self) {
+        self.context.assert_current();
         unsafe {
             gl::BindBuffer(self.r#type, 0);
         }
     }
 
-    pub fn store_f32_data(&self, data: &[f32]) {
+    pub fn store_data<T: Pod>(&self, data: &[T]) {
+        self.context.assert_current();
         unsafe {
             gl::BufferData(
                 self.r#type,
-                (data.len() * mem::size_of::<gl::types::GLfloat>()) as gl::types::GLsizeiptr,
-                &data[0] as *const f32 as *const c_void,
+                mem::size_of_val(data) as gl::types::GLsizeiptr,
+                data.as_ptr() as *const c_void,
                 self.usage,
             );
         }
     }
 
+    pub fn store_f32_data(&self, data: &[f32]) {
+        self.store_data(data);
+    }
+
     pub fn store_i32_data(&self, data: &[i32]) {
-        unsafe {
-            gl::BufferData(
-                self.r#type,
-                (data.len() * mem::size_of::<gl::types::GLint>()) as gl::types::GLsizeiptr,
-                &data[0] as *const i32 as *const c_void,
-                self.usage,
-            );
-        }
+        self.store_data(data);
+    }
+
+    pub(crate) fn target(&self) -> gl::types::GLenum {
+        self.r#type
+    }
+
+    pub(crate) fn usage(&self) -> gl::types::GLenum {
+        self.usage
+    }
+
+    pub(crate) fn context(&self) -> &GlContext {
+        &self.context
+    }
+}
+
+impl Drop for BufferObject {
+    fn drop(&mut self) {
+        self.context.release("buffer", self.id, || unsafe {
+            gl::DeleteBuffers(1, &self.id);
+        });
     }
 }
 
@@ -86,13 +141,14 @@ pub struct VertexAttribute {
 }
 
 impl VertexAttribute {
+    /// `offset` is in bytes from the start of the bound array buffer.
     pub fn new(
         index: u32,
         size: i32,
         r#type: gl::types::GLenum,
         normalized: gl::types::GLboolean,
         stride: gl::types::GLsizei,
-        pointer: *const c_void,
+        offset: usize,
     ) -> VertexAttribute {
         unsafe {
             gl::VertexAttribPointer(
@@ -101,7 +157,7 @@ impl VertexAttribute {
                 r#type,
                 normalized,
                 stride,
-                pointer,
+                offset as *const c_void,
             );
         }
 
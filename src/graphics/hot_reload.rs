@@ -0,0 +1,49 @@
+use std::time::{Duration, Instant};
+
+use super::shaders::Shader;
+
+/// Polls the files of a set of shaders and reloads the ones that changed.
+/// Checking every file on every frame is wasteful, so it only looks once per
+/// `interval`.
+pub struct ShaderWatcher {
+    interval: Duration,
+    last_poll: Option<Instant>,
+}
+
+impl Default for ShaderWatcher {
+    fn default() -> Self {
+        ShaderWatcher::new(Duration::from_millis(500))
+    }
+}
+
+impl ShaderWatcher {
+    pub fn new(interval: Duration) -> ShaderWatcher {
+        ShaderWatcher {
+            interval,
+            last_poll: None,
+        }
+    }
+
+    /// Reloads the changed shaders if the interval elapsed, returning
+    /// whether any of them now uses a new program. Call it at a point in the
+    /// frame where no shader is in the middle of being used, e.g. at the
+    /// start of `render`.
+    pub fn poll<'a>(&mut self, shaders: impl IntoIterator<Item = &'a mut Shader>) -> bool {
+        let now = Instant::now();
+        if self
+            .last_poll
+            .is_some_and(|last_poll| now.duration_since(last_poll) < self.interval)
+        {
+            return false;
+        }
+        self.last_poll = Some(now);
+
+        // Not `any`, every shader has to get the chance to reload.
+        let mut reloaded = false;
+        for shader in shaders {
+            reloaded |= shader.reload_if_changed();
+        }
+
+        reloaded
+    }
+}
@@ -0,0 +1,151 @@
+use std::ptr;
+
+use gl::types::{GLenum, GLsizei};
+
+use super::buffer::{Index, IndexBuffer};
+use super::context::GlContext;
+use super::gl_wrapper::{BufferObject, Vao};
+use super::vertex::{Vertex, VertexBuffer};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Topology {
+    Points,
+    Lines,
+    LineStrip,
+    LineLoop,
+    Triangles,
+    TriangleStrip,
+    TriangleFan,
+}
+
+impl Topology {
+    pub fn gl_mode(self) -> GLenum {
+        match self {
+            Topology::Points => gl::POINTS,
+            Topology::Lines => gl::LINES,
+            Topology::LineStrip => gl::LINE_STRIP,
+            Topology::LineLoop => gl::LINE_LOOP,
+            Topology::Triangles => gl::TRIANGLES,
+            Topology::TriangleStrip => gl::TRIANGLE_STRIP,
+            Topology::TriangleFan => gl::TRIANGLE_FAN,
+        }
+    }
+}
+
+/// Vertex and optional index data uploaded to the GPU together with the
+/// `Vao` describing them, ready to draw.
+pub struct Mesh {
+    vao: Vao,
+    _vertex_buffer: BufferObject,
+    index_buffer: Option<BufferObject>,
+    vertex_count: usize,
+    index_count: usize,
+    index_type: Option<GLenum>,
+    topology: Topology,
+}
+
+impl Mesh {
+    pub fn new<V: Vertex>(context: &GlContext, vertices: &[V], topology: Topology) -> Mesh {
+        let vao = Vao::new(context);
+        let vertex_buffer = VertexBuffer::new(context, &vao, vertices, gl::STATIC_DRAW);
+        vao.unbind();
+
+        Mesh {
+            vao,
+            _vertex_buffer: vertex_buffer.into_buffer().into_object(),
+            index_buffer: None,
+            vertex_count: vertices.len(),
+            index_count: 0,
+            index_type: None,
+            topology,
+        }
+    }
+
+    pub fn indexed<V: Vertex, I: Index>(
+        context: &GlContext,
+        vertices: &[V],
+        indices: &[I],
+        topology: Topology,
+    ) -> Mesh {
+        let vao = Vao::new(context);
+        let vertex_buffer = VertexBuffer::new(context, &vao, vertices, gl::STATIC_DRAW);
+        let index_buffer = IndexBuffer::new(context, indices, gl::STATIC_DRAW);
+        vao.unbind();
+
+        Mesh {
+            vao,
+            _vertex_buffer: vertex_buffer.into_buffer().into_object(),
+            index_buffer: Some(index_buffer.into_buffer().into_object()),
+            vertex_count: vertices.len(),
+            index_count: indices.len(),
+            index_type: Some(I::TYPE),
+            topology,
+        }
+    }
+
+    pub fn draw(&self) {
+        self.draw_instanced(1);
+    }
+
+    /// Draws `instances` copies in one call, shaders tell them apart with
+    /// `gl_InstanceID`.
+    pub fn draw_instanced(&self, instances: u32) {
+        if instances == 0 {
+            return;
+        }
+
+        let mode = self.topology.gl_mode();
+        self.vao.bind();
+
+        unsafe {
+            match (self.index_type, instances) {
+                (Some(index_type), 1) => {
+                    gl::DrawElements(mode, self.index_count as GLsizei, index_type, ptr::null())
+                }
+                (Some(index_type), n) => gl::DrawElementsInstanced(
+                    mode,
+                    self.index_count as GLsizei,
+                    index_type,
+                    ptr::null(),
+                    n as GLsizei,
+                ),
+                (None, 1) => gl::DrawArrays(mode, 0, self.vertex_count as GLsizei),
+                (None, n) => {
+                    gl::DrawArraysInstanced(mode, 0, self.vertex_count as GLsizei, n as GLsizei)
+                }
+            }
+        }
+
+        self.vao.unbind();
+    }
+
+    pub fn vao(&self) -> &Vao {
+        &self.vao
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.vertex_count
+    }
+
+    pub fn index_count(&self) -> usize {
+        self.index_count
+    }
+
+    /// `gl::UNSIGNED_BYTE`, `gl::UNSIGNED_SHORT` or `gl::UNSIGNED_INT`, `None`
+    /// for meshes drawn without indices.
+    pub fn index_type(&self) -> Option<GLenum> {
+        self.index_type
+    }
+
+    pub fn is_indexed(&self) -> bool {
+        self.index_buffer.is_some()
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.topology = topology;
+    }
+}
@@ -1,3 +1,15 @@
+pub mod buffer;
+pub mod context;
+pub mod diagnostics;
+pub mod framebuffer;
 pub mod gl_wrapper;
+pub mod hot_reload;
+pub mod mesh;
+pub mod preprocessor;
+pub mod primitives;
+pub mod reflection;
+pub mod screenshot;
 pub mod shaders;
-pub mod textures;
\ No newline at end of file
+pub mod textures;
+pub mod uniform;
+pub mod vertex;
\ No newline at end of file
@@ -0,0 +1,420 @@
+//! Resolves `#include`s and injects `#version` and `#define`s before a
+//! shader is handed to the driver. Each file gets its own GLSL source string
+//! number through `#line`, so diagnostics point back at the original file
+//! and line.
+
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::errors::ShaderErrors;
+
+use super::diagnostics::SourceMap;
+
+/// Preprocessor settings, cloned and extended to build variants of one
+/// source:
+///
+/// ```ignore
+/// let base = Preprocessor::new().version("330 core").include_path("assets/shaders");
+/// let lit = base.clone().define("LIGHTING", "1");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Preprocessor {
+    version: Option<String>,
+    defines: Vec<(String, String)>,
+    search_paths: Vec<PathBuf>,
+}
+
+/// Preprocessed source along with the files it was assembled from.
+#[derive(Clone, Debug)]
+pub struct PreprocessedSource {
+    pub code: String,
+    pub sources: SourceMap,
+}
+
+impl Preprocessor {
+    pub fn new() -> Preprocessor {
+        Preprocessor::default()
+    }
+
+    /// Replaces the `#version` of the shader, e.g. `"330 core"`.
+    pub fn version(mut self, version: &str) -> Self {
+        self.version = Some(version.to_owned());
+        self
+    }
+
+    /// Adds `#define name value`, redefining `name` if it was already set.
+    pub fn define(mut self, name: &str, value: &str) -> Self {
+        self.defines.retain(|(defined, _)| defined != name);
+        self.defines.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Directory searched for includes not found next to the including file.
+    pub fn include_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.search_paths.push(path.into());
+        self
+    }
+
+    pub fn process(&self, path: impl AsRef<Path>) -> Result<PreprocessedSource, ShaderErrors> {
+        let path = path.as_ref();
+        let source = read(path)?;
+
+        let version = match &self.version {
+            Some(version) => Some(format!("#version {}", version)),
+            None => source
+                .lines()
+                .find(|line| directive(line, "version").is_some())
+                .map(|line| line.trim().to_owned()),
+        };
+
+        let mut code = String::new();
+        if let Some(version) = version {
+            code.push_str(&version);
+            code.push('\n');
+        }
+        for (name, value) in &self.defines {
+            code.push_str(&format!("#define {} {}\n", name, value));
+        }
+
+        let mut state = State {
+            sources: SourceMap::new(),
+            stack: Vec::new(),
+            once: HashSet::new(),
+        };
+        self.expand(path, source, &mut state, &mut code)?;
+
+        Ok(PreprocessedSource {
+            code,
+            sources: state.sources,
+        })
+    }
+
+    fn expand(
+        &self,
+        path: &Path,
+        source: String,
+        state: &mut State,
+        code: &mut String,
+    ) -> Result<(), ShaderErrors> {
+        let canonical = fs::canonicalize(path).unwrap_or_else(|_| path.to_owned());
+        // Guarded files can include each other, only unguarded ones cycle.
+        if state.once.contains(&canonical) {
+            return Ok(());
+        }
+        if state.stack.contains(&canonical) {
+            return Err(ShaderErrors::IncludeCycle(path.to_owned()));
+        }
+
+        let number = state.sources.add(path, source.as_str());
+        state.stack.push(canonical.clone());
+        code.push_str(&format!("#line 1 {}\n", number));
+
+        for (index, line) in source.lines().enumerate() {
+            let line_number = index as u32 + 1;
+
+            if directive(line, "version").is_some() {
+                // Already hoisted to the top, keep the line so numbering holds.
+                code.push('\n');
+            } else if directive(line, "pragma").is_some_and(|rest| rest.trim() == "once") {
+                state.once.insert(canonical.clone());
+                code.push('\n');
+            } else if let Some(rest) = directive(line, "include") {
+                let included = self.resolve(path, line_number, rest.trim())?;
+                let included_source = read(&included)?;
+
+                self.expand(&included, included_source, state, code)?;
+                code.push_str(&format!("#line {} {}\n", line_number + 1, number));
+            } else {
+                code.push_str(line);
+                code.push('\n');
+            }
+        }
+
+        state.stack.pop();
+        Ok(())
+    }
+
+    /// `"file"` is looked up next to `includer` first, `<file>` only in the
+    /// search paths.
+    fn resolve(&self, includer: &Path, line: u32, target: &str) -> Result<PathBuf, ShaderErrors> {
+        let (name, relative) =
+            if let Some(name) = target.strip_prefix('"').and_then(|t| t.strip_suffix('"')) {
+                (name, true)
+            } else if let Some(name) = target.strip_prefix('<').and_then(|t| t.strip_suffix('>')) {
+                (name, false)
+            } else {
+                return Err(ShaderErrors::InvalidInclude {
+                    path: includer.to_owned(),
+                    line,
+                    include: target.to_owned(),
+                });
+            };
+
+        let next_to_includer = includer
+            .parent()
+            .filter(|_| relative)
+            .map(|dir| dir.join(name));
+
+        next_to_includer
+            .into_iter()
+            .chain(self.search_paths.iter().map(|dir| dir.join(name)))
+            .find(|candidate| candidate.is_file())
+            .ok_or_else(|| ShaderErrors::IncludeNotFound {
+                path: includer.to_owned(),
+                line,
+                include: name.to_owned(),
+            })
+    }
+}
+
+struct State {
+    sources: SourceMap,
+    /// Files currently being expanded, to catch include cycles.
+    stack: Vec<PathBuf>,
+    /// Files marked `#pragma once` that were already included.
+    once: HashSet<PathBuf>,
+}
+
+fn read(path: &Path) -> Result<String, ShaderErrors> {
+    fs::read_to_string(path).map_err(|e| ShaderErrors::Io {
+        path: path.to_owned(),
+        source: e,
+    })
+}
+
+/// The rest of `line` if it's the preprocessor directive `name`.
+fn directive<'a>(line: &'a str, name: &str) -> Option<&'a str> {
+    let rest = line
+        .trim_start()
+        .strip_prefix('#')?
+        .trim_start()
+        .strip_prefix(name)?;
+
+    match rest.chars().next() {
+        None => Some(rest),
+        Some(c) if c.is_whitespace() || c == '"' || c == '<' => Some(rest),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+
+    /// Scratch directory under the system temp dir, removed on drop.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(test: &str) -> TempDir {
+            let path =
+                env::temp_dir().join(format!("ashen-preprocessor-{}-{}", test, process::id()));
+            let _ = fs::remove_dir_all(&path);
+            fs::create_dir_all(&path).unwrap();
+            TempDir(path)
+        }
+
+        fn write(&self, name: &str, contents: &str) -> PathBuf {
+            let path = self.0.join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, contents).unwrap();
+            path
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn quoted_includes_look_next_to_the_includer_first() {
+        let dir = TempDir::new("quoted");
+        let main = dir.write("shaders/main.frag", "#include \"common.glsl\"\n");
+        dir.write("shaders/common.glsl", "float local;\n");
+        dir.write("lib/common.glsl", "float library;\n");
+
+        let code = Preprocessor::new()
+            .include_path(dir.0.join("lib"))
+            .process(&main)
+            .unwrap()
+            .code;
+
+        assert!(code.contains("float local;"));
+        assert!(!code.contains("float library;"));
+    }
+
+    #[test]
+    fn quoted_includes_fall_back_to_search_paths() {
+        let dir = TempDir::new("fallback");
+        let main = dir.write("shaders/main.frag", "#include \"common.glsl\"\n");
+        dir.write("lib/common.glsl", "float library;\n");
+
+        let code = Preprocessor::new()
+            .include_path(dir.0.join("lib"))
+            .process(&main)
+            .unwrap()
+            .code;
+
+        assert!(code.contains("float library;"));
+    }
+
+    #[test]
+    fn angle_includes_only_use_search_paths() {
+        let dir = TempDir::new("angle");
+        let main = dir.write("shaders/main.frag", "\n#include <common.glsl>\n");
+        dir.write("shaders/common.glsl", "float local;\n");
+        dir.write("lib/common.glsl", "float library;\n");
+
+        let code = Preprocessor::new()
+            .include_path(dir.0.join("lib"))
+            .process(&main)
+            .unwrap()
+            .code;
+        assert!(code.contains("float library;"));
+        assert!(!code.contains("float local;"));
+
+        let error = Preprocessor::new().process(&main).unwrap_err();
+        assert!(
+            matches!(
+                &error,
+                ShaderErrors::IncludeNotFound { path, line: 2, include }
+                    if *path == main && include == "common.glsl"
+            ),
+            "{:?}",
+            error
+        );
+    }
+
+    #[test]
+    fn malformed_include() {
+        let dir = TempDir::new("malformed");
+        let main = dir.write("main.frag", "#include common.glsl\n");
+
+        let error = Preprocessor::new().process(&main).unwrap_err();
+        assert!(
+            matches!(error, ShaderErrors::InvalidInclude { line: 1, .. }),
+            "{:?}",
+            error
+        );
+    }
+
+    #[test]
+    fn include_cycle() {
+        let dir = TempDir::new("cycle");
+        let main = dir.write("main.frag", "#include \"a.glsl\"\n");
+        let a = dir.write("a.glsl", "#include \"b.glsl\"\n");
+        dir.write("b.glsl", "#include \"a.glsl\"\n");
+
+        // Reported at the include that closes the cycle.
+        match Preprocessor::new().process(&main).unwrap_err() {
+            ShaderErrors::IncludeCycle(path) => assert_eq!(path, a),
+            error => panic!("expected an include cycle, got {:?}", error),
+        }
+    }
+
+    #[test]
+    fn pragma_once_breaks_mutual_includes() {
+        let dir = TempDir::new("mutual");
+        let main = dir.write("main.frag", "#include \"a.glsl\"\n");
+        dir.write("a.glsl", "#pragma once\n#include \"b.glsl\"\nfloat a;\n");
+        dir.write("b.glsl", "#pragma once\n#include \"a.glsl\"\nfloat b;\n");
+
+        let code = Preprocessor::new().process(&main).unwrap().code;
+
+        assert_eq!(code.matches("float a;").count(), 1);
+        assert_eq!(code.matches("float b;").count(), 1);
+        assert!(code.find("float b;") < code.find("float a;"));
+    }
+
+    #[test]
+    fn pragma_once() {
+        let dir = TempDir::new("once");
+        let main = dir.write(
+            "main.frag",
+            "#include \"once.glsl\"\n#include \"once.glsl\"\n#include \"twice.glsl\"\n#include \"twice.glsl\"\n",
+        );
+        dir.write("once.glsl", "#pragma once\nfloat once;\n");
+        dir.write("twice.glsl", "float twice;\n");
+
+        let code = Preprocessor::new().process(&main).unwrap().code;
+
+        assert_eq!(code.matches("float once;").count(), 1);
+        assert_eq!(code.matches("float twice;").count(), 2);
+        assert!(!code.contains("#pragma once"));
+    }
+
+    #[test]
+    fn defines_override_earlier_ones() {
+        let dir = TempDir::new("defines");
+        let main = dir.write("main.frag", "#version 330 core\nvoid main() {}\n");
+
+        let code = Preprocessor::new()
+            .define("LIGHTS", "4")
+            .define("SHADOWS", "1")
+            .define("LIGHTS", "8")
+            .process(&main)
+            .unwrap()
+            .code;
+
+        assert!(code.starts_with("#version 330 core\n#define SHADOWS 1\n#define LIGHTS 8\n"));
+        assert!(!code.contains("#define LIGHTS 4"));
+    }
+
+    #[test]
+    fn version_is_hoisted() {
+        let dir = TempDir::new("version");
+        let main = dir.write(
+            "main.frag",
+            "// A comment first\n#version 330 core\nvoid main() {}\n",
+        );
+
+        let code = Preprocessor::new()
+            .define("A", "1")
+            .process(&main)
+            .unwrap()
+            .code;
+        assert!(code.starts_with("#version 330 core\n#define A 1\n"));
+        assert_eq!(code.matches("#version").count(), 1);
+
+        let code = Preprocessor::new()
+            .version("450 core")
+            .process(&main)
+            .unwrap()
+            .code;
+        assert!(code.starts_with("#version 450 core\n"));
+        assert!(!code.contains("330"));
+    }
+
+    #[test]
+    fn line_directives_map_back_to_files() {
+        let dir = TempDir::new("lines");
+        let main = dir.write(
+            "main.frag",
+            "#version 330 core\n#include \"a.glsl\"\nvoid main() {}\n",
+        );
+        let a = dir.write("a.glsl", "float a;\nfloat b;\n");
+
+        let processed = Preprocessor::new().process(&main).unwrap();
+
+        assert_eq!(
+            processed.code,
+            "#version 330 core\n\
+             #line 1 0\n\
+             \n\
+             #line 1 1\n\
+             float a;\n\
+             float b;\n\
+             #line 3 0\n\
+             void main() {}\n"
+        );
+        assert_eq!(processed.sources.get(0).unwrap().path, main);
+        assert_eq!(processed.sources.get(1).unwrap().path, a);
+        assert!(processed.sources.get(2).is_none());
+    }
+}
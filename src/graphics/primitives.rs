@@ -0,0 +1,610 @@
+//! Procedural meshes. Generation only fills `MeshData` on the CPU, so it
+//! works without a GL context; `MeshData::upload` turns it into a `Mesh`.
+//!
+//! Everything is centered on the origin with Y up, front faces wind
+//! counter-clockwise and `tangent.w` is the sign to use for the bitangent,
+//! `bitangent = cross(normal, tangent.xyz) * tangent.w`.
+
+use std::{
+    collections::HashMap,
+    f32::consts::{FRAC_PI_2, PI, TAU},
+};
+
+use bytemuck::{Pod, Zeroable};
+
+use super::context::GlContext;
+use super::mesh::{Mesh, Topology};
+use super::vertex::Vertex;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable, Vertex)]
+#[repr(C)]
+pub struct PrimitiveVertex {
+    pub position: [f32; 3],
+    pub normal: [f32; 3],
+    pub tangent: [f32; 4],
+    pub uv: [f32; 2],
+}
+
+/// Triangle list ready to upload or to feed physics.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MeshData {
+    pub vertices: Vec<PrimitiveVertex>,
+    pub indices: Vec<u32>,
+}
+
+impl MeshData {
+    pub fn upload(&self, context: &GlContext) -> Mesh {
+        Mesh::indexed(context, &self.vertices, &self.indices, Topology::Triangles)
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = [f32; 3]> + '_ {
+        self.vertices.iter().map(|v| v.position)
+    }
+
+    pub fn triangles(&self) -> impl Iterator<Item = [u32; 3]> + '_ {
+        self.indices.chunks_exact(3).map(|t| [t[0], t[1], t[2]])
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    /// Appends `other`, offsetting its indices.
+    pub fn merge(&mut self, other: MeshData) {
+        let offset = self.vertices.len() as u32;
+        self.vertices.extend(other.vertices);
+        self.indices
+            .extend(other.indices.into_iter().map(|i| i + offset));
+    }
+
+    /// Builds a `columns` x `rows` grid of quads, `vertex(u, v)` gets both in
+    /// `0.0..=1.0`. The triangles face the side `cross(d/du, d/dv)` points to.
+    fn surface(
+        columns: u32,
+        rows: u32,
+        mut vertex: impl FnMut(f32, f32) -> PrimitiveVertex,
+    ) -> MeshData {
+        let (columns, rows) = (columns.max(1), rows.max(1));
+        let mut data = MeshData::default();
+
+        for row in 0..=rows {
+            for column in 0..=columns {
+                data.vertices.push(vertex(
+                    column as f32 / columns as f32,
+                    row as f32 / rows as f32,
+                ));
+            }
+        }
+
+        let stride = columns + 1;
+        for row in 0..rows {
+            for column in 0..columns {
+                let a = row * stride + column;
+                let b = a + 1;
+                let c = b + stride;
+                let d = a + stride;
+                data.indices.extend_from_slice(&[a, b, c, a, c, d]);
+            }
+        }
+
+        data
+    }
+}
+
+/// Single quad in the XY plane facing +Z.
+pub fn quad(width: f32, height: f32) -> MeshData {
+    grid(
+        [0.0; 3],
+        [0.0, 0.0, 1.0],
+        [1.0, 0.0, 0.0],
+        (width, height),
+        (1, 1),
+    )
+}
+
+/// Subdivided plane in the XZ plane facing +Y.
+pub fn plane(width: f32, depth: f32, subdivisions_x: u32, subdivisions_z: u32) -> MeshData {
+    grid(
+        [0.0; 3],
+        [0.0, 1.0, 0.0],
+        [1.0, 0.0, 0.0],
+        (width, depth),
+        (subdivisions_x, subdivisions_z),
+    )
+}
+
+/// Cube with separate vertices per face so every face gets flat normals and
+/// the full texture.
+pub fn cube(size: f32) -> MeshData {
+    let faces = [
+        ([1.0, 0.0, 0.0], [0.0, 0.0, -1.0]),
+        ([-1.0, 0.0, 0.0], [0.0, 0.0, 1.0]),
+        ([0.0, 1.0, 0.0], [1.0, 0.0, 0.0]),
+        ([0.0, -1.0, 0.0], [1.0, 0.0, 0.0]),
+        ([0.0, 0.0, 1.0], [1.0, 0.0, 0.0]),
+        ([0.0, 0.0, -1.0], [-1.0, 0.0, 0.0]),
+    ];
+
+    let mut data = MeshData::default();
+    for (normal, tangent) in faces {
+        data.merge(grid(
+            scale(normal, size * 0.5),
+            normal,
+            tangent,
+            (size, size),
+            (1, 1),
+        ));
+    }
+
+    data
+}
+
+pub fn uv_sphere(radius: f32, segments: u32, rings: u32) -> MeshData {
+    let rings = rings.max(2);
+    let profile = (0..=rings).map(|ring| {
+        let v = ring as f32 / rings as f32;
+        let (sin, cos) = (v * PI - FRAC_PI_2).sin_cos();
+
+        ProfilePoint {
+            radius: radius * cos,
+            y: radius * sin,
+            normal: (cos, sin),
+            v,
+        }
+    });
+
+    lathe(&profile.collect::<Vec<_>>(), segments)
+}
+
+/// Subdivided icosahedron, the triangles are much more even than on a UV
+/// sphere. Vertices along the UV seam are duplicated so textures wrap.
+pub fn icosphere(radius: f32, subdivisions: u32) -> MeshData {
+    let t = (1.0 + 5f32.sqrt()) * 0.5;
+    let mut points: Vec<[f32; 3]> = [
+        [-1.0, t, 0.0],
+        [1.0, t, 0.0],
+        [-1.0, -t, 0.0],
+        [1.0, -t, 0.0],
+        [0.0, -1.0, t],
+        [0.0, 1.0, t],
+        [0.0, -1.0, -t],
+        [0.0, 1.0, -t],
+        [t, 0.0, -1.0],
+        [t, 0.0, 1.0],
+        [-t, 0.0, -1.0],
+        [-t, 0.0, 1.0],
+    ]
+    .into_iter()
+    .map(normalize)
+    .collect();
+
+    #[rustfmt::skip]
+    let mut indices: Vec<u32> = vec![
+        0, 11, 5, 0, 5, 1, 0, 1, 7, 0, 7, 10, 0, 10, 11,
+        1, 5, 9, 5, 11, 4, 11, 10, 2, 10, 7, 6, 7, 1, 8,
+        3, 9, 4, 3, 4, 2, 3, 2, 6, 3, 6, 8, 3, 8, 9,
+        4, 9, 5, 2, 4, 11, 6, 2, 10, 8, 6, 7, 9, 8, 1,
+    ];
+
+    for _ in 0..subdivisions {
+        let mut midpoints = HashMap::new();
+        let mut midpoint = |a: u32, b: u32| {
+            *midpoints.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                let (pa, pb) = (points[a as usize], points[b as usize]);
+                points.push(normalize(scale(add(pa, pb), 0.5)));
+                points.len() as u32 - 1
+            })
+        };
+
+        indices = indices
+            .chunks_exact(3)
+            .flat_map(|t| {
+                let (a, b, c) = (t[0], t[1], t[2]);
+                let (ab, bc, ca) = (midpoint(a, b), midpoint(b, c), midpoint(c, a));
+                [a, ab, ca, b, bc, ab, c, ca, bc, ab, bc, ca]
+            })
+            .collect();
+    }
+
+    let mut data = MeshData {
+        vertices: points
+            .into_iter()
+            .map(|normal| {
+                let longitude = normal[0].atan2(normal[2]);
+                let (sin, cos) = longitude.sin_cos();
+
+                PrimitiveVertex {
+                    position: scale(normal, radius),
+                    normal,
+                    tangent: [cos, 0.0, -sin, 1.0],
+                    uv: [
+                        0.5 + longitude / TAU,
+                        0.5 + normal[1].clamp(-1.0, 1.0).asin() / PI,
+                    ],
+                }
+            })
+            .collect(),
+        indices,
+    };
+
+    fix_uv_seam(&mut data);
+    data
+}
+
+pub fn cylinder(radius: f32, height: f32, segments: u32) -> MeshData {
+    let half = height * 0.5;
+    let mut data = lathe(
+        &[
+            ProfilePoint {
+                radius,
+                y: -half,
+                normal: (1.0, 0.0),
+                v: 0.0,
+            },
+            ProfilePoint {
+                radius,
+                y: half,
+                normal: (1.0, 0.0),
+                v: 1.0,
+            },
+        ],
+        segments,
+    );
+
+    data.merge(disc(radius, -half, false, segments));
+    data.merge(disc(radius, half, true, segments));
+    data
+}
+
+pub fn cone(radius: f32, height: f32, segments: u32) -> MeshData {
+    let half = height * 0.5;
+    let slant = (radius * radius + height * height).sqrt();
+    let normal = (height / slant, radius / slant);
+
+    let mut data = lathe(
+        &[
+            ProfilePoint {
+                radius,
+                y: -half,
+                normal,
+                v: 0.0,
+            },
+            ProfilePoint {
+                radius: 0.0,
+                y: half,
+                normal,
+                v: 1.0,
+            },
+        ],
+        segments,
+    );
+
+    data.merge(disc(radius, -half, false, segments));
+    data
+}
+
+/// Torus around the Y axis, `major_radius` to the center of the tube.
+pub fn torus(
+    major_radius: f32,
+    minor_radius: f32,
+    major_segments: u32,
+    minor_segments: u32,
+) -> MeshData {
+    MeshData::surface(major_segments.max(3), minor_segments.max(3), |u, v| {
+        let (sin_major, cos_major) = (u * TAU).sin_cos();
+        let (sin_minor, cos_minor) = (v * TAU).sin_cos();
+
+        let center = [major_radius * sin_major, 0.0, major_radius * cos_major];
+        let normal = [cos_minor * sin_major, sin_minor, cos_minor * cos_major];
+
+        PrimitiveVertex {
+            position: add(center, scale(normal, minor_radius)),
+            normal,
+            tangent: [cos_major, 0.0, -sin_major, 1.0],
+            uv: [u, v],
+        }
+    })
+}
+
+/// Cylinder of `height` capped by two hemispheres, so the total height is
+/// `height + 2 * radius`. The usual character collider shape.
+pub fn capsule(radius: f32, height: f32, segments: u32, rings: u32) -> MeshData {
+    let rings = rings.max(1);
+    let half = height * 0.5;
+    let total = PI * radius + height;
+
+    let mut profile = Vec::new();
+    for (offset, range) in [(-half, -FRAC_PI_2..0.0), (half, 0.0..FRAC_PI_2)] {
+        for ring in 0..=rings {
+            let angle = range.start + (range.end - range.start) * ring as f32 / rings as f32;
+            let (sin, cos) = angle.sin_cos();
+            let arc = (angle + FRAC_PI_2) * radius + if offset > 0.0 { height } else { 0.0 };
+
+            profile.push(ProfilePoint {
+                radius: radius * cos,
+                y: offset + radius * sin,
+                normal: (cos, sin),
+                v: arc / total,
+            });
+        }
+    }
+
+    lathe(&profile, segments)
+}
+
+fn grid(
+    center: [f32; 3],
+    normal: [f32; 3],
+    tangent: [f32; 3],
+    (width, height): (f32, f32),
+    (columns, rows): (u32, u32),
+) -> MeshData {
+    let bitangent = cross(normal, tangent);
+
+    MeshData::surface(columns, rows, |u, v| PrimitiveVertex {
+        position: add(
+            center,
+            add(
+                scale(tangent, (u - 0.5) * width),
+                scale(bitangent, (v - 0.5) * height),
+            ),
+        ),
+        normal,
+        tangent: [tangent[0], tangent[1], tangent[2], 1.0],
+        uv: [u, v],
+    })
+}
+
+/// A point of a profile swept around the Y axis, `normal` being its
+/// (outward, up) components.
+struct ProfilePoint {
+    radius: f32,
+    y: f32,
+    normal: (f32, f32),
+    v: f32,
+}
+
+/// Sweeps a bottom to top profile around the Y axis, starting at +Z and
+/// turning towards +X.
+fn lathe(profile: &[ProfilePoint], segments: u32) -> MeshData {
+    let rows = profile.len() as u32 - 1;
+
+    MeshData::surface(segments.max(3), rows, |u, v| {
+        let point = &profile[(v * rows as f32).round() as usize];
+        let (sin, cos) = (u * TAU).sin_cos();
+
+        PrimitiveVertex {
+            position: [point.radius * sin, point.y, point.radius * cos],
+            normal: [point.normal.0 * sin, point.normal.1, point.normal.0 * cos],
+            tangent: [cos, 0.0, -sin, 1.0],
+            uv: [u, point.v],
+        }
+    })
+}
+
+/// Flat cap at height `y`, facing +Y if `up` else -Y.
+fn disc(radius: f32, y: f32, up: bool, segments: u32) -> MeshData {
+    let segments = segments.max(3);
+    let facing = if up { 1.0 } else { -1.0 };
+    let vertex = |x: f32, z: f32| PrimitiveVertex {
+        position: [x, y, z],
+        normal: [0.0, facing, 0.0],
+        tangent: [1.0, 0.0, 0.0, 1.0],
+        uv: [0.5 + 0.5 * x / radius, 0.5 - facing * 0.5 * z / radius],
+    };
+
+    let mut data = MeshData::default();
+    data.vertices.push(vertex(0.0, 0.0));
+
+    for segment in 0..=segments {
+        let (sin, cos) = (segment as f32 / segments as f32 * TAU).sin_cos();
+        data.vertices.push(vertex(radius * sin, radius * cos));
+    }
+
+    for segment in 1..=segments {
+        match up {
+            true => data.indices.extend_from_slice(&[0, segment, segment + 1]),
+            false => data.indices.extend_from_slice(&[0, segment + 1, segment]),
+        }
+    }
+
+    data
+}
+
+/// Triangles crossing the `u = 0/1` seam would interpolate across the whole
+/// texture, give them copies of their low-`u` vertices shifted by one.
+fn fix_uv_seam(data: &mut MeshData) {
+    let mut copies = HashMap::new();
+
+    for triangle in 0..data.triangle_count() {
+        let us = data.indices[triangle * 3..triangle * 3 + 3]
+            .iter()
+            .map(|&i| data.vertices[i as usize].uv[0]);
+        let (min, max) = us.fold((f32::MAX, f32::MIN), |(min, max), u| {
+            (min.min(u), max.max(u))
+        });
+
+        if max - min <= 0.5 {
+            continue;
+        }
+
+        for corner in triangle * 3..triangle * 3 + 3 {
+            let index = data.indices[corner];
+            if data.vertices[index as usize].uv[0] >= 0.5 {
+                continue;
+            }
+
+            data.indices[corner] = *copies.entry(index).or_insert_with(|| {
+                let mut vertex = data.vertices[index as usize];
+                vertex.uv[0] += 1.0;
+                data.vertices.push(vertex);
+                data.vertices.len() as u32 - 1
+            });
+        }
+    }
+}
+
+fn add(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn scale(a: [f32; 3], s: f32) -> [f32; 3] {
+    [a[0] * s, a[1] * s, a[2] * s]
+}
+
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn normalize(a: [f32; 3]) -> [f32; 3] {
+    let length = (a[0] * a[0] + a[1] * a[1] + a[2] * a[2]).sqrt();
+    scale(a, 1.0 / length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f32 = 1e-4;
+
+    fn primitives() -> Vec<(&'static str, MeshData)> {
+        vec![
+            ("quad", quad(2.0, 1.0)),
+            ("plane", plane(4.0, 2.0, 3, 2)),
+            ("cube", cube(1.5)),
+            ("uv_sphere", uv_sphere(1.0, 16, 8)),
+            ("icosphere", icosphere(1.0, 2)),
+            ("cylinder", cylinder(0.5, 2.0, 12)),
+            ("cone", cone(0.5, 1.0, 12)),
+            ("torus", torus(1.0, 0.25, 16, 8)),
+            ("capsule", capsule(0.5, 1.0, 12, 4)),
+        ]
+    }
+
+    fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+        add(a, scale(b, -1.0))
+    }
+
+    fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+        a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+    }
+
+    fn length(a: [f32; 3]) -> f32 {
+        dot(a, a).sqrt()
+    }
+
+    #[test]
+    fn vertex_and_index_counts() {
+        let counts = |data: &MeshData| (data.vertices.len(), data.indices.len());
+
+        assert_eq!(counts(&quad(1.0, 1.0)), (4, 6));
+        assert_eq!(counts(&plane(1.0, 1.0, 3, 2)), (4 * 3, 6 * 3 * 2));
+        assert_eq!(counts(&cube(1.0)), (6 * 4, 6 * 6));
+        assert_eq!(counts(&uv_sphere(1.0, 16, 8)), (17 * 9, 6 * 16 * 8));
+        assert_eq!(
+            counts(&cylinder(1.0, 1.0, 12)),
+            (13 * 2 + 2 * 14, 6 * 12 + 2 * 3 * 12)
+        );
+        assert_eq!(counts(&cone(1.0, 1.0, 12)), (13 * 2 + 14, 6 * 12 + 3 * 12));
+        assert_eq!(counts(&torus(1.0, 0.25, 16, 8)), (17 * 9, 6 * 16 * 8));
+        assert_eq!(counts(&capsule(0.5, 1.0, 12, 4)), (13 * 10, 6 * 12 * 9));
+
+        // Seam copies come on top of the 10 * 4^n + 2 shared vertices.
+        let icosphere = icosphere(1.0, 2);
+        assert_eq!(icosphere.triangle_count(), 20 * 16);
+        assert!(icosphere.vertices.len() >= 10 * 16 + 2);
+    }
+
+    #[test]
+    fn indices_in_range() {
+        for (name, data) in primitives() {
+            assert_eq!(data.indices.len() % 3, 0, "{}", name);
+            assert!(
+                data.indices
+                    .iter()
+                    .all(|&i| (i as usize) < data.vertices.len()),
+                "{} has an index out of range",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn normals_are_unit_length() {
+        for (name, data) in primitives() {
+            for vertex in &data.vertices {
+                assert!(
+                    (length(vertex.normal) - 1.0).abs() < EPSILON,
+                    "{} has normal {:?}",
+                    name,
+                    vertex.normal
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn triangles_wind_counter_clockwise() {
+        for (name, data) in primitives() {
+            for triangle in data.triangles() {
+                let [a, b, c] = triangle.map(|i| data.vertices[i as usize]);
+                let face = cross(sub(b.position, a.position), sub(c.position, a.position));
+                // Collapsed triangles at poles and tips have no facing.
+                if length(face) < EPSILON * EPSILON {
+                    continue;
+                }
+
+                let normal = add(a.normal, add(b.normal, c.normal));
+                assert!(
+                    dot(face, normal) > 0.0,
+                    "{} triangle {:?} winds against its normals",
+                    name,
+                    triangle
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn tangents_are_orthogonal_to_normals() {
+        for (name, data) in primitives() {
+            for vertex in &data.vertices {
+                let tangent = [vertex.tangent[0], vertex.tangent[1], vertex.tangent[2]];
+
+                assert!(
+                    dot(tangent, vertex.normal).abs() < EPSILON,
+                    "{} has tangent {:?} for normal {:?}",
+                    name,
+                    vertex.tangent,
+                    vertex.normal
+                );
+                assert!((length(tangent) - 1.0).abs() < EPSILON, "{}", name);
+                assert_eq!(vertex.tangent[3].abs(), 1.0, "{}", name);
+            }
+        }
+    }
+
+    #[test]
+    fn uvs_in_unit_range() {
+        for (name, data) in primitives() {
+            for vertex in &data.vertices {
+                let [u, v] = vertex.uv;
+                // The icosphere's seam copies continue past 1 so a repeating
+                // texture wraps instead of squeezing across the whole width.
+                let max_u = if name == "icosphere" { 1.5 } else { 1.0 };
+
+                assert!(
+                    (-EPSILON..=max_u + EPSILON).contains(&u)
+                        && (-EPSILON..=1.0 + EPSILON).contains(&v),
+                    "{} has uv {:?}",
+                    name,
+                    vertex.uv
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,229 @@
+//! What a linked program exposes, as reported by `glGetActiveUniform` and
+//! `glGetActiveAttrib`.
+
+use std::{collections::HashMap, ffi::CString};
+
+use gl::types::{GLchar, GLenum, GLint, GLsizei, GLuint};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ActiveVariable {
+    pub name: String,
+    pub location: GLint,
+    /// `gl::FLOAT_VEC3`, `gl::SAMPLER_2D`...
+    pub r#type: GLenum,
+    /// Element count, 1 unless it's an array.
+    pub size: GLint,
+}
+
+impl ActiveVariable {
+    pub fn type_name(&self) -> &'static str {
+        type_name(self.r#type)
+    }
+}
+
+type GetActive =
+    unsafe fn(GLuint, GLuint, GLsizei, *mut GLsizei, *mut GLint, *mut GLenum, *mut GLchar);
+type GetLocation = unsafe fn(GLuint, *const GLchar) -> GLint;
+
+/// Uniforms outside of uniform blocks. Arrays are listed under their plain
+/// name as well as one entry per element, `lights` and `lights[2]` both
+/// resolve.
+pub(crate) unsafe fn active_uniforms(program: GLuint) -> HashMap<String, ActiveVariable> {
+    let mut uniforms = active_variables(
+        program,
+        gl::ACTIVE_UNIFORMS,
+        gl::ACTIVE_UNIFORM_MAX_LENGTH,
+        gl::GetActiveUniform,
+        gl::GetUniformLocation,
+    );
+
+    let arrays: Vec<ActiveVariable> = uniforms
+        .values()
+        .filter(|uniform| uniform.size > 1)
+        .cloned()
+        .collect();
+    for array in arrays {
+        for index in 0..array.size {
+            let name = format!("{}[{}]", array.name, index);
+            let location = location(program, &name, gl::GetUniformLocation);
+
+            uniforms.insert(
+                name.clone(),
+                ActiveVariable {
+                    name,
+                    location,
+                    r#type: array.r#type,
+                    size: array.size - index,
+                },
+            );
+        }
+    }
+
+    uniforms
+}
+
+pub(crate) unsafe fn active_attributes(program: GLuint) -> HashMap<String, ActiveVariable> {
+    active_variables(
+        program,
+        gl::ACTIVE_ATTRIBUTES,
+        gl::ACTIVE_ATTRIBUTE_MAX_LENGTH,
+        gl::GetActiveAttrib,
+        gl::GetAttribLocation,
+    )
+}
+
+unsafe fn active_variables(
+    program: GLuint,
+    count: GLenum,
+    max_length: GLenum,
+    get_active: GetActive,
+    get_location: GetLocation,
+) -> HashMap<String, ActiveVariable> {
+    let mut variables = HashMap::new();
+
+    let mut variable_count = 0;
+    gl::GetProgramiv(program, count, &mut variable_count);
+    let mut name_capacity = 0;
+    gl::GetProgramiv(program, max_length, &mut name_capacity);
+
+    let mut buffer = vec![0u8; name_capacity.max(1) as usize];
+    for index in 0..variable_count.max(0) as GLuint {
+        let mut length = 0;
+        let mut size = 0;
+        let mut r#type = 0;
+        get_active(
+            program,
+            index,
+            buffer.len() as GLsizei,
+            &mut length,
+            &mut size,
+            &mut r#type,
+            buffer.as_mut_ptr() as *mut GLchar,
+        );
+
+        let name = String::from_utf8_lossy(&buffer[..length.max(0) as usize]);
+        // Arrays are reported as `name[0]`.
+        let name = name.strip_suffix("[0]").unwrap_or(&name).to_owned();
+
+        // Members of uniform blocks and built-ins like `gl_VertexID` have no
+        // location and can't be set individually.
+        let location = location(program, &name, get_location);
+        if location < 0 {
+            continue;
+        }
+
+        variables.insert(
+            name.clone(),
+            ActiveVariable {
+                name,
+                location,
+                r#type,
+                size,
+            },
+        );
+    }
+
+    variables
+}
+
+unsafe fn location(program: GLuint, name: &str, get_location: GetLocation) -> GLint {
+    match CString::new(name) {
+        Ok(name) => get_location(program, name.as_ptr()),
+        Err(_) => -1,
+    }
+}
+
+pub fn is_sampler(r#type: GLenum) -> bool {
+    matches!(
+        r#type,
+        gl::SAMPLER_1D
+            | gl::SAMPLER_2D
+            | gl::SAMPLER_3D
+            | gl::SAMPLER_CUBE
+            | gl::SAMPLER_1D_SHADOW
+            | gl::SAMPLER_2D_SHADOW
+            | gl::SAMPLER_1D_ARRAY
+            | gl::SAMPLER_2D_ARRAY
+            | gl::SAMPLER_1D_ARRAY_SHADOW
+            | gl::SAMPLER_2D_ARRAY_SHADOW
+            | gl::SAMPLER_2D_MULTISAMPLE
+            | gl::SAMPLER_2D_MULTISAMPLE_ARRAY
+            | gl::SAMPLER_CUBE_SHADOW
+            | gl::SAMPLER_BUFFER
+            | gl::SAMPLER_2D_RECT
+            | gl::SAMPLER_2D_RECT_SHADOW
+            | gl::INT_SAMPLER_1D
+            | gl::INT_SAMPLER_2D
+            | gl::INT_SAMPLER_3D
+            | gl::INT_SAMPLER_CUBE
+            | gl::INT_SAMPLER_1D_ARRAY
+            | gl::INT_SAMPLER_2D_ARRAY
+            | gl::INT_SAMPLER_2D_MULTISAMPLE
+            | gl::INT_SAMPLER_2D_MULTISAMPLE_ARRAY
+            | gl::INT_SAMPLER_BUFFER
+            | gl::INT_SAMPLER_2D_RECT
+            | gl::UNSIGNED_INT_SAMPLER_1D
+            | gl::UNSIGNED_INT_SAMPLER_2D
+            | gl::UNSIGNED_INT_SAMPLER_3D
+            | gl::UNSIGNED_INT_SAMPLER_CUBE
+            | gl::UNSIGNED_INT_SAMPLER_1D_ARRAY
+            | gl::UNSIGNED_INT_SAMPLER_2D_ARRAY
+            | gl::UNSIGNED_INT_SAMPLER_2D_MULTISAMPLE
+            | gl::UNSIGNED_INT_SAMPLER_2D_MULTISAMPLE_ARRAY
+            | gl::UNSIGNED_INT_SAMPLER_BUFFER
+            | gl::UNSIGNED_INT_SAMPLER_2D_RECT
+    )
+}
+
+/// GLSL spelling of a type, for messages.
+pub fn type_name(r#type: GLenum) -> &'static str {
+    match r#type {
+        gl::FLOAT => "float",
+        gl::FLOAT_VEC2 => "vec2",
+        gl::FLOAT_VEC3 => "vec3",
+        gl::FLOAT_VEC4 => "vec4",
+        gl::INT => "int",
+        gl::INT_VEC2 => "ivec2",
+        gl::INT_VEC3 => "ivec3",
+        gl::INT_VEC4 => "ivec4",
+        gl::UNSIGNED_INT => "uint",
+        gl::UNSIGNED_INT_VEC2 => "uvec2",
+        gl::UNSIGNED_INT_VEC3 => "uvec3",
+        gl::UNSIGNED_INT_VEC4 => "uvec4",
+        gl::BOOL => "bool",
+        gl::BOOL_VEC2 => "bvec2",
+        gl::BOOL_VEC3 => "bvec3",
+        gl::BOOL_VEC4 => "bvec4",
+        gl::FLOAT_MAT2 => "mat2",
+        gl::FLOAT_MAT3 => "mat3",
+        gl::FLOAT_MAT4 => "mat4",
+        gl::FLOAT_MAT2x3 => "mat2x3",
+        gl::FLOAT_MAT2x4 => "mat2x4",
+        gl::FLOAT_MAT3x2 => "mat3x2",
+        gl::FLOAT_MAT3x4 => "mat3x4",
+        gl::FLOAT_MAT4x2 => "mat4x2",
+        gl::FLOAT_MAT4x3 => "mat4x3",
+        gl::DOUBLE => "double",
+        gl::DOUBLE_VEC2 => "dvec2",
+        gl::DOUBLE_VEC3 => "dvec3",
+        gl::DOUBLE_VEC4 => "dvec4",
+        gl::DOUBLE_MAT2 => "dmat2",
+        gl::DOUBLE_MAT3 => "dmat3",
+        gl::DOUBLE_MAT4 => "dmat4",
+        gl::DOUBLE_MAT2x3 => "dmat2x3",
+        gl::DOUBLE_MAT2x4 => "dmat2x4",
+        gl::DOUBLE_MAT3x2 => "dmat3x2",
+        gl::DOUBLE_MAT3x4 => "dmat3x4",
+        gl::DOUBLE_MAT4x2 => "dmat4x2",
+        gl::DOUBLE_MAT4x3 => "dmat4x3",
+        gl::SAMPLER_1D => "sampler1D",
+        gl::SAMPLER_2D => "sampler2D",
+        gl::SAMPLER_3D => "sampler3D",
+        gl::SAMPLER_CUBE => "samplerCube",
+        gl::SAMPLER_2D_SHADOW => "sampler2DShadow",
+        gl::SAMPLER_2D_ARRAY => "sampler2DArray",
+        gl::SAMPLER_CUBE_SHADOW => "samplerCubeShadow",
+        r#type if is_sampler(r#type) => "sampler",
+        _ => "unknown",
+    }
+}
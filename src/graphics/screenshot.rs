@@ -0,0 +1,72 @@
+use std::{
+    os::raw::c_void,
+    path::{Path, PathBuf},
+    thread::{self, JoinHandle},
+};
+
+use image::RgbaImage;
+
+use crate::errors::Errors;
+
+/// Reads the given framebuffer, `0` being the window's, as tightly packed
+/// RGBA8 rows. GL returns them bottom row first. The read framebuffer, pack
+/// buffer and pack alignment are restored afterwards.
+pub fn read_pixels(framebuffer: gl::types::GLuint, width: i32, height: i32) -> Vec<u8> {
+    let mut pixels = vec![0u8; width.max(0) as usize * height.max(0) as usize * 4];
+
+    unsafe {
+        let (mut read_framebuffer, mut pack_buffer, mut alignment) = (0, 0, 0);
+        gl::GetIntegerv(gl::READ_FRAMEBUFFER_BINDING, &mut read_framebuffer);
+        gl::GetIntegerv(gl::PIXEL_PACK_BUFFER_BINDING, &mut pack_buffer);
+        gl::GetIntegerv(gl::PACK_ALIGNMENT, &mut alignment);
+
+        gl::BindFramebuffer(gl::READ_FRAMEBUFFER, framebuffer);
+        // A bound pack buffer would take the pixels instead of `pixels`.
+        gl::BindBuffer(gl::PIXEL_PACK_BUFFER, 0);
+        gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+        gl::ReadPixels(
+            0,
+            0,
+            width,
+            height,
+            gl::RGBA,
+            gl::UNSIGNED_BYTE,
+            pixels.as_mut_ptr() as *mut c_void,
+        );
+
+        gl::PixelStorei(gl::PACK_ALIGNMENT, alignment);
+        gl::BindBuffer(gl::PIXEL_PACK_BUFFER, pack_buffer as gl::types::GLuint);
+        gl::BindFramebuffer(gl::READ_FRAMEBUFFER, read_framebuffer as gl::types::GLuint);
+    }
+
+    pixels
+}
+
+/// Same as `read_pixels` but flipped into a top row first image, undoing the
+/// `flipv` textures get on load.
+pub fn capture(framebuffer: gl::types::GLuint, width: i32, height: i32) -> RgbaImage {
+    let pixels = read_pixels(framebuffer, width, height);
+    let mut image = RgbaImage::from_raw(width.max(0) as u32, height.max(0) as u32, pixels)
+        .expect("pixel buffer matches the framebuffer size");
+
+    image::imageops::flip_vertical_in_place(&mut image);
+    image
+}
+
+/// The format is picked from the file extension.
+pub fn save(image: &RgbaImage, path: impl AsRef<Path>) -> Result<(), Errors> {
+    image.save(path)?;
+
+    Ok(())
+}
+
+/// Encodes and writes the image on its own thread so a capture only costs
+/// the frame the `glReadPixels` itself.
+pub fn save_in_background(
+    image: RgbaImage,
+    path: impl Into<PathBuf>,
+) -> JoinHandle<Result<(), Errors>> {
+    let path = path.into();
+
+    thread::spawn(move || save(&image, path))
+}
@@ -1,32 +1,75 @@
 use std::{
-    ffi::{CStr, CString},
-    fs::File,
-    io::Read,
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    ffi::CString,
+    fmt,
+    fs,
+    mem,
+    path::{Path, PathBuf},
     ptr,
-    str
+    time::SystemTime,
 };
 
-use gl::types::{GLchar, GLint};
+use gl::types::{GLchar, GLenum, GLint, GLsizei, GLuint};
 use glm::{Matrix4, Vec3};
 
+use crate::errors::ShaderErrors;
 use crate::logger;
 
+use super::context::GlContext;
+use super::diagnostics::{self, SourceMap};
+use super::preprocessor::Preprocessor;
+use super::reflection::{self, ActiveVariable};
+use super::uniform::{self, Uniform};
+
 pub struct Shader {
-    pub id: u32,
+    id: u32,
 
     fragment_shader: u32,
     geometry_shader: u32,
     vertex_shader: u32,
+
+    sources: Vec<StageSource>,
+
+    uniforms: HashMap<String, ActiveVariable>,
+    attributes: HashMap<String, ActiveVariable>,
+    /// Uniforms already warned about, so a bad setter called every frame
+    /// warns once.
+    warned: RefCell<HashSet<String>>,
+    /// Uniforms set through `set` with the number of elements set, carried
+    /// over to the new program on reload.
+    assigned: RefCell<HashMap<String, usize>>,
+
+    context: GlContext,
+}
+
+/// What a stage was built from, kept to rebuild it when the files change.
+struct StageSource {
+    stage: ShaderStage,
+    path: String,
+    preprocessor: Preprocessor,
+    /// Every file the stage was assembled from with its modification time.
+    files: Vec<(PathBuf, Option<SystemTime>)>,
 }
 
 impl Shader {
-    pub fn new() -> Shader {
+    pub fn new(context: &GlContext) -> Shader {
+        context.assert_current();
         let mut shader: Shader = Shader {
             id: 0,
             
             fragment_shader: 0,
             geometry_shader: 0,
             vertex_shader: 0,
+
+            sources: Vec::new(),
+
+            uniforms: HashMap::new(),
+            attributes: HashMap::new(),
+            warned: RefCell::new(HashSet::new()),
+            assigned: RefCell::new(HashMap::new()),
+
+            context: context.clone(),
         };
 
         unsafe {
@@ -36,162 +79,438 @@ impl Shader {
         shader
     }
 
-    pub fn load_fragment_shader(&mut self, source: &str) {
-        let mut shader_file = File::open(source)
-            .unwrap_or_else(|e| {
-                logger::error!("{}", e);
-                panic!("Failed to open {}", source);
-            });
+    pub fn load_fragment_shader(&mut self, source: &str) -> Result<(), ShaderErrors> {
+        self.load_shader(ShaderStage::Fragment, source, &Preprocessor::default())
+    }
 
-        let mut shader_code = String::new();
-        shader_file
-            .read_to_string(&mut shader_code)
-            .expect("Failed to read fragment shader code");
+    pub fn load_vertex_shader(&mut self, source: &str) -> Result<(), ShaderErrors> {
+        self.load_shader(ShaderStage::Vertex, source, &Preprocessor::default())
+    }
 
-        let f_shader_code = CString::new(shader_code.as_bytes()).unwrap();
+    pub fn load_geometry_shader(&mut self, source: &str) -> Result<(), ShaderErrors> {
+        self.load_shader(ShaderStage::Geometry, source, &Preprocessor::default())
+    }
 
-        unsafe {
-            self.fragment_shader = gl::CreateShader(gl::FRAGMENT_SHADER);
-            gl::ShaderSource(
-                self.fragment_shader,
-                1,
-                &f_shader_code.as_ptr(),
-                ptr::null(),
-            );
-            gl::CompileShader(self.fragment_shader);
-            self.check_compile_errors(self.fragment_shader, "FRAGMENT");
-
-            gl::AttachShader(self.id, self.fragment_shader);
-        }
-    }
-
-    pub fn load_vertex_shader(&mut self, source: &str) {
-        let mut shader_file = File::open(source)
-            .unwrap_or_else(|e| {
-                logger::error!("{}", e);
-                panic!("Failed to open {}", source);
-            });
-        
-        let mut shader_code = String::new();
-        shader_file
-            .read_to_string(&mut shader_code)
-            .expect("Failed to read fragment shader code");
-
-        let v_shader_code = CString::new(shader_code.as_bytes()).unwrap();
+    /// Compiles `source` for `stage` after running it through `preprocessor`,
+    /// which supplies the `#version`, `#define`s and include paths.
+    pub fn load_shader(&mut self, stage: ShaderStage, source: &str, preprocessor: &Preprocessor) -> Result<(), ShaderErrors> {
+        self.compile_shader(stage, source, preprocessor).inspect_err(|e| logger::error!("{}", e))
+    }
+
+    pub fn link_program(&mut self) -> Result<(), ShaderErrors> {
+        self.context.assert_current();
+        let result = unsafe {
+            gl::LinkProgram(self.id);
+            self.check_link_errors()
+        };
+
+        for shader in [&mut self.fragment_shader, &mut self.vertex_shader, &mut self.geometry_shader] {
+            if *shader != 0 {
+                unsafe {
+                    gl::DetachShader(self.id, *shader);
+                    gl::DeleteShader(*shader);
+                }
+                *shader = 0;
+            }
+        }
+
+        if result.is_ok() {
+            unsafe {
+                self.uniforms = reflection::active_uniforms(self.id);
+                self.attributes = reflection::active_attributes(self.id);
+            }
+            self.warned.borrow_mut().clear();
+        }
+
+        result.inspect_err(|e| logger::error!("{}", e))
+    }
+
+    /// Active uniform `name`, known after a successful `link_program`.
+    pub fn uniform(&self, name: &str) -> Option<&ActiveVariable> {
+        self.uniforms.get(name)
+    }
+
+    pub fn uniforms(&self) -> impl Iterator<Item = &ActiveVariable> {
+        self.uniforms.values()
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&ActiveVariable> {
+        self.attributes.get(name)
+    }
+
+    pub fn attributes(&self) -> impl Iterator<Item = &ActiveVariable> {
+        self.attributes.values()
+    }
+
+    /// Whether any file the loaded stages were built from changed on disk.
+    pub fn has_changed(&self) -> bool {
+        self.sources
+            .iter()
+            .flat_map(|source| &source.files)
+            .any(|(path, modified)| modified_time(path) != *modified)
+    }
 
+    /// Rebuilds the program from the files it was loaded from. The new
+    /// program only replaces the current one if every stage compiles and it
+    /// links, otherwise the current one stays in use. Uniforms set through
+    /// `set` keep their values if the new program still has them with the
+    /// same type, anything set with raw GL calls has to be set again.
+    pub fn reload(&mut self) -> Result<(), ShaderErrors> {
+        let mut shader = Shader::new(&self.context);
+        for source in &self.sources {
+            shader.compile_shader(source.stage, &source.path, &source.preprocessor)?;
+        }
+        shader.link_program()?;
         unsafe {
-            self.vertex_shader = gl::CreateShader(gl::VERTEX_SHADER);
-            gl::ShaderSource(
-                self.vertex_shader,
-                1,
-                &v_shader_code.as_ptr(),
-                ptr::null(),
-            );
-            gl::CompileShader(self.vertex_shader);
-            self.check_compile_errors(self.vertex_shader, "VERTEX");
-
-            gl::AttachShader(self.id, self.vertex_shader);
-        }
-    }
-
-    pub fn load_geometry_shader(&mut self, source: &str) {
-        let mut shader_file = File::open(source)
-            .unwrap_or_else(|e| {
-                logger::error!("{}", e);
-                panic!("Failed to open {}", source);
-            });
-        
-        let mut shader_code = String::new();
-        shader_file
-            .read_to_string(&mut shader_code)
-            .expect("Failed to read fragment shader code");
-
-        let v_shader_code = CString::new(shader_code.as_bytes()).unwrap();
+            shader.copy_uniforms(self);
+        }
+
+        logger::info!("Reloaded shader program {} as {}", self.id, shader.id);
+        mem::swap(self, &mut shader);
+
+        Ok(())
+    }
+
+    /// Reloads if the files changed, returning whether a new program is now
+    /// in use. Call it between frames, not between binding the program and
+    /// drawing with it.
+    pub fn reload_if_changed(&mut self) -> bool {
+        if !self.has_changed() {
+            return false;
+        }
+
+        // Only retry a broken shader once it's edited again.
+        for (path, modified) in self.sources.iter_mut().flat_map(|source| &mut source.files) {
+            *modified = modified_time(path);
+        }
+
+        match self.reload() {
+            Ok(()) => true,
+            Err(e) => {
+                let paths: Vec<&str> = self.sources.iter().map(|source| source.path.as_str()).collect();
+                logger::error!(
+                    "Failed to reload shader program {} from {}, keeping the old one: {}",
+                    self.id, paths.join(", "), e
+                );
+                false
+            }
+        }
+    }
 
+    fn compile_shader(&mut self, stage: ShaderStage, source: &str, preprocessor: &Preprocessor) -> Result<(), ShaderErrors> {
+        let preprocessed = preprocessor.process(source)?;
+        let sources = preprocessed.sources;
+        let files = sources
+            .files()
+            .iter()
+            .map(|file| (file.path.clone(), modified_time(&file.path)))
+            .collect();
+        let shader_code = CString::new(preprocessed.code).map_err(|_| ShaderErrors::NulInSource(source.into()))?;
+
+        self.context.assert_current();
         unsafe {
-            self.geometry_shader = gl::CreateShader(gl::GEOMETRY_SHADER);
-            gl::ShaderSource(
-                self.geometry_shader,
-                1,
-                &v_shader_code.as_ptr(),
-                ptr::null(),
-            );
-            gl::CompileShader(self.geometry_shader);
-            self.check_compile_errors(self.geometry_shader, "GEOMETRY");
+            let shader = gl::CreateShader(stage.gl_type());
+            gl::ShaderSource(shader, 1, &shader_code.as_ptr(), ptr::null());
+            gl::CompileShader(shader);
+
+            if let Err(e) = check_compile_errors(shader, stage, source, &sources) {
+                gl::DeleteShader(shader);
+                return Err(e);
+            }
+
+            gl::AttachShader(self.id, shader);
 
-            gl::AttachShader(self.id, self.geometry_shader);
+            let slot = match stage {
+                ShaderStage::Fragment => &mut self.fragment_shader,
+                ShaderStage::Geometry => &mut self.geometry_shader,
+                ShaderStage::Vertex => &mut self.vertex_shader,
+            };
+            if *slot != 0 {
+                gl::DetachShader(self.id, *slot);
+                gl::DeleteShader(*slot);
+            }
+            *slot = shader;
         }
+
+        self.sources.retain(|source| source.stage != stage);
+        self.sources.push(StageSource {
+            stage,
+            path: source.to_owned(),
+            preprocessor: preprocessor.clone(),
+            files,
+        });
+
+        Ok(())
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
     }
 
-    pub fn link_program(&self) {
+    pub fn use_program(&self) {
+        self.context.assert_current();
         unsafe {
-            gl::LinkProgram(self.id);
-            self.check_compile_errors(self.id, "PROGRAM");
+            gl::UseProgram(self.id);
+        }
+    }
 
-            if self.fragment_shader != 0 { gl::DeleteShader(self.fragment_shader); }
-            if self.vertex_shader != 0 { gl::DeleteShader(self.vertex_shader); }
-            if self.geometry_shader != 0 { gl::DeleteShader(self.geometry_shader); }
+    /// Sets uniform `name` to `value`, see `Uniform` for what can be set.
+    /// Skips, with a warning, any uniform the program doesn't have, whose
+    /// type doesn't match or that is too short for an array `value`.
+    ///
+    /// With GL 4.1 or `ARB_separate_shader_objects` this works on any
+    /// program, otherwise it has to be bound with `use_program` first, which
+    /// debug builds check.
+    pub fn set<U: Uniform>(&self, name: &str, value: U) {
+        self.context.assert_current();
+
+        let Some(uniform) = self.uniforms.get(name) else {
+            self.warn_once(name, || format!("Shader program {} has no active uniform {:?}, it may have been optimized out", self.id, name));
+            return;
+        };
+        if !U::accepts(uniform.r#type) {
+            self.warn_once(name, || format!(
+                "Uniform {:?} of shader program {} is a {}, can't set it from a {}",
+                name, self.id, uniform.type_name(), U::glsl_type()
+            ));
+            return;
+        }
+        if value.count() > uniform.size as usize {
+            self.warn_once(name, || format!(
+                "Uniform {:?} of shader program {} holds {} elements, can't set {}",
+                name, self.id, uniform.size, value.count()
+            ));
+            return;
+        }
+        self.assign(name, value.count());
+
+        unsafe {
+            if self.context.has_program_uniforms() {
+                value.set(Some(self.id), uniform.location);
+            } else {
+                debug_assert!(
+                    current_program() == self.id,
+                    "Shader program {} has to be bound to set {:?}",
+                    self.id, name
+                );
+                value.set(None, uniform.location);
+            }
         }
     }
 
-    pub unsafe fn use_program(&self) {
-        gl::UseProgram(self.id);
+    pub fn set_bool(&self, name: &str, value: bool) {
+        self.set(name, value);
     }
 
-    pub unsafe fn set_bool(&self, name: &CStr, value: bool) {
-        gl::Uniform1i(gl::GetUniformLocation(self.id, name.as_ptr()), value as i32);
+    /// Also sets samplers, to the texture unit they read from.
+    pub fn set_int(&self, name: &str, value: i32) {
+        self.set(name, value);
     }
 
-    pub unsafe fn set_int(&self, name: &CStr, value: i32) {
-        gl::Uniform1i(gl::GetUniformLocation(self.id, name.as_ptr()), value);
+    pub fn set_float(&self, name: &str, value: f32) {
+        self.set(name, value);
     }
 
-    pub unsafe fn set_float(&self, name: &CStr, value: f32) {
-        gl::Uniform1f(gl::GetUniformLocation(self.id, name.as_ptr()), value);
+    pub fn set_vector_3(&self, name: &str, value: &Vec3) {
+        self.set(name, value);
     }
 
-    pub unsafe fn set_vector_3(&self, name: &CStr, value: &Vec3) {
-        gl::Uniform1fv(gl::GetUniformLocation(self.id, name.as_ptr()), 1, value.as_array().as_ptr());
+    pub fn set_vec_3(&self, name: &str, x: f32, y: f32, z: f32) {
+        self.set(name, glm::vec3(x, y, z));
     }
 
-    pub unsafe fn set_vec_3(&self, name: &CStr, x: f32, y: f32, z: f32) {
-        gl::Uniform3f(gl::GetUniformLocation(self.id, name.as_ptr()), x, y, z);
+    pub fn set_mat_4(&self, name: &str, value: &Matrix4<f32>) {
+        self.set(name, value);
     }
 
-    pub unsafe fn set_mat_4(&self, name: &CStr, value: &Matrix4<f32>) {
-        gl::UniformMatrix4fv(
-            gl::GetUniformLocation(self.id, name.as_ptr()),
-            1,
-            gl::FALSE,
-            value.as_array().as_ptr() as *const f32
-        );
+    /// Remembers that `count` elements of `name` were set, without
+    /// allocating when it's set every frame.
+    fn assign(&self, name: &str, count: usize) {
+        let mut assigned = self.assigned.borrow_mut();
+        match assigned.get_mut(name) {
+            Some(assigned) => *assigned = (*assigned).max(count),
+            None => {
+                assigned.insert(name.to_owned(), count);
+            }
+        }
     }
 
-    unsafe fn check_compile_errors(&self, shader: u32, type_: &str) {
-        let mut success = gl::FALSE as GLint;
-        let mut info_log = Vec::with_capacity(1024);
-
-        info_log.set_len(1024 - 1);
-
-        if type_ != "PROGRAM" {
-            gl::GetShaderiv(shader, gl::COMPILE_STATUS, &mut success);
-            if success != gl::TRUE as GLint {
-                gl::GetShaderInfoLog(shader, 1024, ptr::null_mut(), info_log.as_mut_ptr() as *mut GLchar);
-                println!("ERROR::SHADER_COMPILATION_ERROR of type: {}\n{}\n \
-                          -- --------------------------------------------------- -- ",
-                         type_,
-                         str::from_utf8(&info_log).unwrap());
+    /// Gives every uniform `old` had set the same value in this program,
+    /// reading them back from `old`. Called on a freshly linked program
+    /// before it replaces `old`, a bound `old` is replaced by this one.
+    unsafe fn copy_uniforms(&self, old: &Shader) {
+        let current = current_program();
+        let program = match self.context.has_program_uniforms() {
+            true => Some(self.id),
+            false => {
+                gl::UseProgram(self.id);
+                None
             }
+        };
 
-        } else {
-            gl::GetProgramiv(shader, gl::LINK_STATUS, &mut success);
-            if success != gl::TRUE as GLint {
-                gl::GetProgramInfoLog(shader, 1024, ptr::null_mut(), info_log.as_mut_ptr() as *mut GLchar);
-                println!("ERROR::PROGRAM_LINKING_ERROR of type: {}\n{}\n \
-                          -- --------------------------------------------------- -- ",
-                         type_,
-                         str::from_utf8(&info_log).unwrap());
+        for (name, &count) in old.assigned.borrow().iter() {
+            for element in element_names(name, count) {
+                let (Some(from), Some(to)) = (old.uniforms.get(&element), self.uniforms.get(&element)) else {
+                    continue;
+                };
+                if from.r#type == to.r#type && uniform::copy(old.id, from.location, program, to.location, to.r#type) {
+                    self.assign(&element, 1);
+                }
             }
         }
+
+        gl::UseProgram(if current == old.id { self.id } else { current });
+    }
+
+    fn warn_once(&self, name: &str, message: impl FnOnce() -> String) {
+        if self.warned.borrow_mut().insert(name.to_owned()) {
+            logger::warn!("{}", message());
+        }
+    }
+
+    unsafe fn check_link_errors(&self) -> Result<(), ShaderErrors> {
+        let mut success = gl::FALSE as GLint;
+        gl::GetProgramiv(self.id, gl::LINK_STATUS, &mut success);
+
+        let log = info_log(self.id, gl::GetProgramiv, gl::GetProgramInfoLog);
+        if success != gl::TRUE as GLint {
+            return Err(ShaderErrors::Link { log });
+        }
+        if !log.is_empty() {
+            logger::warn!("Shader program {} linked with warnings:\n{}", self.id, log);
+        }
+
+        Ok(())
+    }
+}
+
+unsafe fn check_compile_errors(
+    shader: u32,
+    stage: ShaderStage,
+    path: &str,
+    sources: &SourceMap,
+) -> Result<(), ShaderErrors> {
+    let mut success = gl::FALSE as GLint;
+    gl::GetShaderiv(shader, gl::COMPILE_STATUS, &mut success);
+
+    let log = info_log(shader, gl::GetShaderiv, gl::GetShaderInfoLog);
+    let diagnostics = diagnostics::parse_info_log(&log, sources);
+    if success != gl::TRUE as GLint {
+        return Err(ShaderErrors::Compile {
+            stage,
+            path: path.into(),
+            log,
+            diagnostics,
+        });
     }
-}
\ No newline at end of file
+    if !diagnostics.is_empty() {
+        logger::warn!("{} shader {} compiled with warnings:\n{}", stage, path, diagnostics::render(&diagnostics));
+    }
+
+    Ok(())
+}
+
+unsafe fn current_program() -> GLuint {
+    let mut program = 0;
+    gl::GetIntegerv(gl::CURRENT_PROGRAM, &mut program);
+    program as GLuint
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+/// The uniforms setting `count` elements of `name` writes, `lights[1]` with
+/// two elements is `lights[1]` and `lights[2]`.
+fn element_names(name: &str, count: usize) -> Vec<String> {
+    if count <= 1 {
+        return vec![name.to_owned()];
+    }
+
+    let (array, first) = name
+        .strip_suffix(']')
+        .and_then(|name| name.rsplit_once('['))
+        .and_then(|(array, index)| Some((array, index.parse().ok()?)))
+        .unwrap_or((name, 0));
+
+    (first..first + count).map(|index| format!("{}[{}]", array, index)).collect()
+}
+
+/// The whole info log, sized with `GL_INFO_LOG_LENGTH` so long logs aren't
+/// cut off.
+unsafe fn info_log(
+    object: u32,
+    get_iv: unsafe fn(u32, GLenum, *mut GLint),
+    get_info_log: unsafe fn(u32, GLsizei, *mut GLsizei, *mut GLchar),
+) -> String {
+    let mut capacity = 0;
+    get_iv(object, gl::INFO_LOG_LENGTH, &mut capacity);
+    if capacity <= 0 {
+        return String::new();
+    }
+
+    let mut info_log = vec![0u8; capacity as usize];
+    let mut length = 0;
+
+    get_info_log(object, info_log.len() as GLsizei, &mut length, info_log.as_mut_ptr() as *mut GLchar);
+    info_log.truncate(length.max(0) as usize);
+
+    String::from_utf8_lossy(&info_log).trim_end().to_owned()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShaderStage {
+    Vertex,
+    Fragment,
+    Geometry,
+}
+
+impl ShaderStage {
+    pub fn gl_type(self) -> gl::types::GLenum {
+        match self {
+            ShaderStage::Vertex => gl::VERTEX_SHADER,
+            ShaderStage::Fragment => gl::FRAGMENT_SHADER,
+            ShaderStage::Geometry => gl::GEOMETRY_SHADER,
+        }
+    }
+}
+
+impl fmt::Display for ShaderStage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ShaderStage::Vertex => write!(f, "Vertex"),
+            ShaderStage::Fragment => write!(f, "Fragment"),
+            ShaderStage::Geometry => write!(f, "Geometry"),
+        }
+    }
+}
+
+impl Drop for Shader {
+    fn drop(&mut self) {
+        self.context.release("shader program", self.id, || unsafe {
+            for shader in [self.fragment_shader, self.geometry_shader, self.vertex_shader] {
+                if shader != 0 {
+                    gl::DeleteShader(shader);
+                }
+            }
+            gl::DeleteProgram(self.id);
+        });
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::element_names;
+
+    #[test]
+    fn element_names_of_plain_uniforms() {
+        assert_eq!(element_names("transform", 1), ["transform"]);
+        assert_eq!(element_names("lights[2]", 1), ["lights[2]"]);
+    }
+
+    #[test]
+    fn element_names_of_arrays() {
+        assert_eq!(element_names("lights", 3), ["lights[0]", "lights[1]", "lights[2]"]);
+        assert_eq!(element_names("lights[1]", 2), ["lights[1]", "lights[2]"]);
+        assert_eq!(element_names("material.weights", 2), ["material.weights[0]", "material.weights[1]"]);
+    }
+}
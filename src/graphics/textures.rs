@@ -3,14 +3,17 @@ use std::{os::raw::c_void, path::Path};
 extern crate alloc;
 use alloc::ffi::CString;
 
+use super::context::GlContext;
 
 pub struct Texture {
-    pub id: u32,
+    id: u32,
     target: TextureType,
+    context: GlContext,
 }
 
 impl Texture {
-    pub fn new(source: &str, target: TextureType, wrapping: TextureWrapping, filtering: TextureFiltering, mipmap_filtering: TextureFiltering) -> Texture {
+    pub fn new(context: &GlContext, source: &str, target: TextureType, wrapping: TextureWrapping, filtering: TextureFiltering, mipmap_filtering: TextureFiltering) -> Texture {
+        context.assert_current();
         let image = image::open(source).unwrap_or_else(|e| {
             panic!("{}", e)
         });
@@ -20,7 +23,7 @@ impl Texture {
         let wrap: i32;
         let filter: i32;
         let mipmap_filter: i32;
-        let mut texture = Texture { id: 0, target: target.clone() };
+        let mut texture = Texture { id: 0, target, context: context.clone() };
 
         let file_path = Path::new(source);
         let format = match file_path.extension() {
@@ -105,21 +108,38 @@ impl Texture {
         texture
     }
 
-    pub unsafe fn bind(&self) {
-        match self.target {
-            TextureType::Texture2D => gl::BindTexture(gl::TEXTURE_2D, self.id),
-            TextureType::Texture3D => gl::BindTexture(gl::TEXTURE_3D, self.id),
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Binds to the active texture unit.
+    pub fn bind(&self) {
+        self.context.assert_current();
+        unsafe {
+            match self.target {
+                TextureType::Texture2D => gl::BindTexture(gl::TEXTURE_2D, self.id),
+                TextureType::Texture3D => gl::BindTexture(gl::TEXTURE_3D, self.id),
+            }
         }
     }
 
     pub fn set_uniform(&self, target: &str, shader: u32, value: i32) {
         let c_str = CString::new(target).expect("Failed to create c_string");
+        self.context.assert_current();
         unsafe {
             gl::Uniform1i(gl::GetUniformLocation(shader, c_str.as_ptr()), value);
         }
     }
 }
 
+impl Drop for Texture {
+    fn drop(&mut self) {
+        self.context.release("texture", self.id, || unsafe {
+            gl::DeleteTextures(1, &self.id);
+        });
+    }
+}
+
 #[derive(Clone, Copy)]
 pub enum TextureType {
     Texture2D,
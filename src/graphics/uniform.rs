@@ -0,0 +1,471 @@
+//! Rust values that can be uploaded to GLSL uniforms with `Shader::set`.
+//!
+//! Arrays and slices of any `UniformElement` set GLSL arrays, so a `[f32; 3]`
+//! is a `float[3]`, use `glm::Vec3` (or `cgmath::Vector3` with the `cgmath`
+//! feature) for a `vec3`. Every scalar, vector and matrix type of GLSL 4.x
+//! has one, `double` ones need a GL 4.0 context.
+
+use std::slice;
+
+use gl::types::{GLenum, GLint, GLsizei, GLuint};
+
+use super::reflection;
+
+pub trait Uniform {
+    /// Whether a uniform of GL type `gl_type` can be set from this type.
+    fn accepts(gl_type: GLenum) -> bool;
+
+    /// GLSL spelling of the type, for messages.
+    fn glsl_type() -> &'static str;
+
+    /// Number of array elements this sets.
+    fn count(&self) -> usize {
+        1
+    }
+
+    /// Uploads to `location` of `program` with `glProgramUniform*`, or with
+    /// `glUniform*` to the bound program for `None`.
+    ///
+    /// # Safety
+    ///
+    /// Needs a current GL context supporting `glProgramUniform*` for a
+    /// `Some` program, and a program bound otherwise. Its uniform at
+    /// `location` has to be of a type `accepts` allows and have room for
+    /// `count` elements.
+    unsafe fn set(&self, program: Option<GLuint>, location: GLint);
+}
+
+/// A `Uniform` that can also be uploaded as an element of an array.
+pub trait UniformElement: Uniform + Sized {
+    /// # Safety
+    ///
+    /// Same as `Uniform::set`, with room for `values.len()` elements.
+    unsafe fn set_slice(values: &[Self], program: Option<GLuint>, location: GLint);
+}
+
+/// Texture unit a sampler reads from, `TextureUnit(1)` for `gl::TEXTURE1`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct TextureUnit(pub i32);
+
+/// Calls `gl::$program_upload` on `Some` program, `gl::$upload` for the
+/// bound one otherwise.
+macro_rules! upload {
+    ($program:expr, $upload:ident, $program_upload:ident, $($arg:expr),+) => {
+        match $program {
+            Some(program) => gl::$program_upload(program, $($arg),+),
+            None => gl::$upload($($arg),+),
+        }
+    };
+}
+
+macro_rules! uniform_element {
+    ($ty:ty, $glsl:literal, |$gl_type:ident| $accepts:expr, |$values:ident, $program:ident, $location:ident| $upload:expr) => {
+        impl Uniform for $ty {
+            fn accepts($gl_type: GLenum) -> bool {
+                $accepts
+            }
+
+            fn glsl_type() -> &'static str {
+                $glsl
+            }
+
+            unsafe fn set(&self, program: Option<GLuint>, location: GLint) {
+                Self::set_slice(slice::from_ref(self), program, location);
+            }
+        }
+
+        impl UniformElement for $ty {
+            unsafe fn set_slice($values: &[Self], $program: Option<GLuint>, $location: GLint) {
+                $upload
+            }
+        }
+    };
+}
+
+/// Vectors and scalars, laid out as consecutive `$scalar`s and uploaded with
+/// one of the `glUniform*v` functions or its `glProgramUniform*v` version.
+macro_rules! vector_uniform {
+    ($ty:ty, $glsl:literal, $gl_type:expr, $scalar:ty, $upload:ident, $program_upload:ident) => {
+        uniform_element!(
+            $ty,
+            $glsl,
+            |gl_type| gl_type == $gl_type,
+            |values, program, location| {
+                upload!(
+                    program,
+                    $upload,
+                    $program_upload,
+                    location,
+                    values.len() as GLsizei,
+                    values.as_ptr() as *const $scalar
+                )
+            }
+        );
+    };
+}
+
+/// Column major matrices of `$scalar`, uploaded with one of the
+/// `glUniformMatrix*v` functions or its `glProgramUniformMatrix*v` version.
+macro_rules! matrix_uniform {
+    ($ty:ty, $glsl:literal, $gl_type:expr, $scalar:ty, $upload:ident, $program_upload:ident) => {
+        uniform_element!(
+            $ty,
+            $glsl,
+            |gl_type| gl_type == $gl_type,
+            |values, program, location| {
+                upload!(
+                    program,
+                    $upload,
+                    $program_upload,
+                    location,
+                    values.len() as GLsizei,
+                    gl::FALSE,
+                    values.as_ptr() as *const $scalar
+                )
+            }
+        );
+    };
+}
+
+/// Bool vectors, which GL takes as ints. `$components` bools each.
+macro_rules! bool_vector_uniform {
+    ($ty:ty, $glsl:literal, $gl_type:expr, $components:literal, $upload:ident, $program_upload:ident) => {
+        uniform_element!(
+            $ty,
+            $glsl,
+            |gl_type| gl_type == $gl_type,
+            |values, program, location| {
+                let values: Vec<i32> = slice::from_raw_parts(
+                    values.as_ptr() as *const bool,
+                    values.len() * $components,
+                )
+                .iter()
+                .map(|&value| value as i32)
+                .collect();
+                upload!(
+                    program,
+                    $upload,
+                    $program_upload,
+                    location,
+                    (values.len() / $components) as GLsizei,
+                    values.as_ptr()
+                )
+            }
+        );
+    };
+}
+
+vector_uniform!(f32, "float", gl::FLOAT, f32, Uniform1fv, ProgramUniform1fv);
+vector_uniform!(
+    f64,
+    "double",
+    gl::DOUBLE,
+    f64,
+    Uniform1dv,
+    ProgramUniform1dv
+);
+uniform_element!(
+    i32,
+    "int",
+    |gl_type| gl_type == gl::INT || gl_type == gl::BOOL || reflection::is_sampler(gl_type),
+    |values, program, location| upload!(
+        program,
+        Uniform1iv,
+        ProgramUniform1iv,
+        location,
+        values.len() as GLsizei,
+        values.as_ptr()
+    )
+);
+uniform_element!(
+    u32,
+    "uint",
+    |gl_type| gl_type == gl::UNSIGNED_INT || gl_type == gl::BOOL,
+    |values, program, location| upload!(
+        program,
+        Uniform1uiv,
+        ProgramUniform1uiv,
+        location,
+        values.len() as GLsizei,
+        values.as_ptr()
+    )
+);
+uniform_element!(
+    bool,
+    "bool",
+    |gl_type| gl_type == gl::BOOL,
+    |values, program, location| {
+        let values: Vec<i32> = values.iter().map(|&value| value as i32).collect();
+        upload!(
+            program,
+            Uniform1iv,
+            ProgramUniform1iv,
+            location,
+            values.len() as GLsizei,
+            values.as_ptr()
+        )
+    }
+);
+uniform_element!(
+    TextureUnit,
+    "sampler",
+    |gl_type| reflection::is_sampler(gl_type),
+    |values, program, location| {
+        upload!(
+            program,
+            Uniform1iv,
+            ProgramUniform1iv,
+            location,
+            values.len() as GLsizei,
+            values.as_ptr() as *const i32
+        )
+    }
+);
+
+#[rustfmt::skip]
+mod glm_uniforms {
+    use super::*;
+
+    vector_uniform!(glm::Vec2, "vec2", gl::FLOAT_VEC2, f32, Uniform2fv, ProgramUniform2fv);
+    vector_uniform!(glm::Vec3, "vec3", gl::FLOAT_VEC3, f32, Uniform3fv, ProgramUniform3fv);
+    vector_uniform!(glm::Vec4, "vec4", gl::FLOAT_VEC4, f32, Uniform4fv, ProgramUniform4fv);
+    vector_uniform!(glm::IVec2, "ivec2", gl::INT_VEC2, i32, Uniform2iv, ProgramUniform2iv);
+    vector_uniform!(glm::IVec3, "ivec3", gl::INT_VEC3, i32, Uniform3iv, ProgramUniform3iv);
+    vector_uniform!(glm::IVec4, "ivec4", gl::INT_VEC4, i32, Uniform4iv, ProgramUniform4iv);
+    vector_uniform!(glm::UVec2, "uvec2", gl::UNSIGNED_INT_VEC2, u32, Uniform2uiv, ProgramUniform2uiv);
+    vector_uniform!(glm::UVec3, "uvec3", gl::UNSIGNED_INT_VEC3, u32, Uniform3uiv, ProgramUniform3uiv);
+    vector_uniform!(glm::UVec4, "uvec4", gl::UNSIGNED_INT_VEC4, u32, Uniform4uiv, ProgramUniform4uiv);
+    vector_uniform!(glm::DVec2, "dvec2", gl::DOUBLE_VEC2, f64, Uniform2dv, ProgramUniform2dv);
+    vector_uniform!(glm::DVec3, "dvec3", gl::DOUBLE_VEC3, f64, Uniform3dv, ProgramUniform3dv);
+    vector_uniform!(glm::DVec4, "dvec4", gl::DOUBLE_VEC4, f64, Uniform4dv, ProgramUniform4dv);
+    bool_vector_uniform!(glm::BVec2, "bvec2", gl::BOOL_VEC2, 2, Uniform2iv, ProgramUniform2iv);
+    bool_vector_uniform!(glm::BVec3, "bvec3", gl::BOOL_VEC3, 3, Uniform3iv, ProgramUniform3iv);
+    bool_vector_uniform!(glm::BVec4, "bvec4", gl::BOOL_VEC4, 4, Uniform4iv, ProgramUniform4iv);
+
+    matrix_uniform!(glm::Mat2, "mat2", gl::FLOAT_MAT2, f32, UniformMatrix2fv, ProgramUniformMatrix2fv);
+    matrix_uniform!(glm::Mat3, "mat3", gl::FLOAT_MAT3, f32, UniformMatrix3fv, ProgramUniformMatrix3fv);
+    matrix_uniform!(glm::Mat4, "mat4", gl::FLOAT_MAT4, f32, UniformMatrix4fv, ProgramUniformMatrix4fv);
+    matrix_uniform!(glm::Mat2x3, "mat2x3", gl::FLOAT_MAT2x3, f32, UniformMatrix2x3fv, ProgramUniformMatrix2x3fv);
+    matrix_uniform!(glm::Mat2x4, "mat2x4", gl::FLOAT_MAT2x4, f32, UniformMatrix2x4fv, ProgramUniformMatrix2x4fv);
+    matrix_uniform!(glm::Mat3x2, "mat3x2", gl::FLOAT_MAT3x2, f32, UniformMatrix3x2fv, ProgramUniformMatrix3x2fv);
+    matrix_uniform!(glm::Mat3x4, "mat3x4", gl::FLOAT_MAT3x4, f32, UniformMatrix3x4fv, ProgramUniformMatrix3x4fv);
+    matrix_uniform!(glm::Mat4x2, "mat4x2", gl::FLOAT_MAT4x2, f32, UniformMatrix4x2fv, ProgramUniformMatrix4x2fv);
+    matrix_uniform!(glm::Mat4x3, "mat4x3", gl::FLOAT_MAT4x3, f32, UniformMatrix4x3fv, ProgramUniformMatrix4x3fv);
+    matrix_uniform!(glm::DMat2, "dmat2", gl::DOUBLE_MAT2, f64, UniformMatrix2dv, ProgramUniformMatrix2dv);
+    matrix_uniform!(glm::DMat3, "dmat3", gl::DOUBLE_MAT3, f64, UniformMatrix3dv, ProgramUniformMatrix3dv);
+    matrix_uniform!(glm::DMat4, "dmat4", gl::DOUBLE_MAT4, f64, UniformMatrix4dv, ProgramUniformMatrix4dv);
+    matrix_uniform!(glm::DMat2x3, "dmat2x3", gl::DOUBLE_MAT2x3, f64, UniformMatrix2x3dv, ProgramUniformMatrix2x3dv);
+    matrix_uniform!(glm::DMat2x4, "dmat2x4", gl::DOUBLE_MAT2x4, f64, UniformMatrix2x4dv, ProgramUniformMatrix2x4dv);
+    matrix_uniform!(glm::DMat3x2, "dmat3x2", gl::DOUBLE_MAT3x2, f64, UniformMatrix3x2dv, ProgramUniformMatrix3x2dv);
+    matrix_uniform!(glm::DMat3x4, "dmat3x4", gl::DOUBLE_MAT3x4, f64, UniformMatrix3x4dv, ProgramUniformMatrix3x4dv);
+    matrix_uniform!(glm::DMat4x2, "dmat4x2", gl::DOUBLE_MAT4x2, f64, UniformMatrix4x2dv, ProgramUniformMatrix4x2dv);
+    matrix_uniform!(glm::DMat4x3, "dmat4x3", gl::DOUBLE_MAT4x3, f64, UniformMatrix4x3dv, ProgramUniformMatrix4x3dv);
+}
+
+#[cfg(feature = "cgmath")]
+#[rustfmt::skip]
+mod cgmath_uniforms {
+    use super::*;
+
+    vector_uniform!(cgmath::Vector2<f32>, "vec2", gl::FLOAT_VEC2, f32, Uniform2fv, ProgramUniform2fv);
+    vector_uniform!(cgmath::Vector3<f32>, "vec3", gl::FLOAT_VEC3, f32, Uniform3fv, ProgramUniform3fv);
+    vector_uniform!(cgmath::Vector4<f32>, "vec4", gl::FLOAT_VEC4, f32, Uniform4fv, ProgramUniform4fv);
+    vector_uniform!(cgmath::Vector2<i32>, "ivec2", gl::INT_VEC2, i32, Uniform2iv, ProgramUniform2iv);
+    vector_uniform!(cgmath::Vector3<i32>, "ivec3", gl::INT_VEC3, i32, Uniform3iv, ProgramUniform3iv);
+    vector_uniform!(cgmath::Vector4<i32>, "ivec4", gl::INT_VEC4, i32, Uniform4iv, ProgramUniform4iv);
+    vector_uniform!(cgmath::Vector2<u32>, "uvec2", gl::UNSIGNED_INT_VEC2, u32, Uniform2uiv, ProgramUniform2uiv);
+    vector_uniform!(cgmath::Vector3<u32>, "uvec3", gl::UNSIGNED_INT_VEC3, u32, Uniform3uiv, ProgramUniform3uiv);
+    vector_uniform!(cgmath::Vector4<u32>, "uvec4", gl::UNSIGNED_INT_VEC4, u32, Uniform4uiv, ProgramUniform4uiv);
+    vector_uniform!(cgmath::Vector2<f64>, "dvec2", gl::DOUBLE_VEC2, f64, Uniform2dv, ProgramUniform2dv);
+    vector_uniform!(cgmath::Vector3<f64>, "dvec3", gl::DOUBLE_VEC3, f64, Uniform3dv, ProgramUniform3dv);
+    vector_uniform!(cgmath::Vector4<f64>, "dvec4", gl::DOUBLE_VEC4, f64, Uniform4dv, ProgramUniform4dv);
+    bool_vector_uniform!(cgmath::Vector2<bool>, "bvec2", gl::BOOL_VEC2, 2, Uniform2iv, ProgramUniform2iv);
+    bool_vector_uniform!(cgmath::Vector3<bool>, "bvec3", gl::BOOL_VEC3, 3, Uniform3iv, ProgramUniform3iv);
+    bool_vector_uniform!(cgmath::Vector4<bool>, "bvec4", gl::BOOL_VEC4, 4, Uniform4iv, ProgramUniform4iv);
+
+    matrix_uniform!(cgmath::Matrix2<f32>, "mat2", gl::FLOAT_MAT2, f32, UniformMatrix2fv, ProgramUniformMatrix2fv);
+    matrix_uniform!(cgmath::Matrix3<f32>, "mat3", gl::FLOAT_MAT3, f32, UniformMatrix3fv, ProgramUniformMatrix3fv);
+    matrix_uniform!(cgmath::Matrix4<f32>, "mat4", gl::FLOAT_MAT4, f32, UniformMatrix4fv, ProgramUniformMatrix4fv);
+    matrix_uniform!(cgmath::Matrix2<f64>, "dmat2", gl::DOUBLE_MAT2, f64, UniformMatrix2dv, ProgramUniformMatrix2dv);
+    matrix_uniform!(cgmath::Matrix3<f64>, "dmat3", gl::DOUBLE_MAT3, f64, UniformMatrix3dv, ProgramUniformMatrix3dv);
+    matrix_uniform!(cgmath::Matrix4<f64>, "dmat4", gl::DOUBLE_MAT4, f64, UniformMatrix4dv, ProgramUniformMatrix4dv);
+}
+
+impl<T: UniformElement, const N: usize> Uniform for [T; N] {
+    fn accepts(gl_type: GLenum) -> bool {
+        T::accepts(gl_type)
+    }
+
+    fn glsl_type() -> &'static str {
+        T::glsl_type()
+    }
+
+    fn count(&self) -> usize {
+        N
+    }
+
+    unsafe fn set(&self, program: Option<GLuint>, location: GLint) {
+        T::set_slice(self, program, location);
+    }
+}
+
+impl<T: UniformElement> Uniform for [T] {
+    fn accepts(gl_type: GLenum) -> bool {
+        T::accepts(gl_type)
+    }
+
+    fn glsl_type() -> &'static str {
+        T::glsl_type()
+    }
+
+    fn count(&self) -> usize {
+        self.len()
+    }
+
+    unsafe fn set(&self, program: Option<GLuint>, location: GLint) {
+        T::set_slice(self, program, location);
+    }
+}
+
+impl<T: UniformElement> Uniform for Vec<T> {
+    fn accepts(gl_type: GLenum) -> bool {
+        T::accepts(gl_type)
+    }
+
+    fn glsl_type() -> &'static str {
+        T::glsl_type()
+    }
+
+    fn count(&self) -> usize {
+        self.len()
+    }
+
+    unsafe fn set(&self, program: Option<GLuint>, location: GLint) {
+        T::set_slice(self, program, location);
+    }
+}
+
+impl<T: Uniform + ?Sized> Uniform for &T {
+    fn accepts(gl_type: GLenum) -> bool {
+        T::accepts(gl_type)
+    }
+
+    fn glsl_type() -> &'static str {
+        T::glsl_type()
+    }
+
+    fn count(&self) -> usize {
+        (**self).count()
+    }
+
+    unsafe fn set(&self, program: Option<GLuint>, location: GLint) {
+        (**self).set(program, location);
+    }
+}
+
+/// Copies the value of the uniform at `from_location` of program `from` to
+/// `to_location` of program `to`, or of the bound one for `None`. Both are of
+/// GL type `gl_type`. Returns `false` for types it can't copy, those are left
+/// as they are.
+///
+/// # Safety
+///
+/// Same as `Uniform::set` for `to`, with a uniform of `gl_type` at
+/// `from_location` in `from`.
+pub(crate) unsafe fn copy(
+    from: GLuint,
+    from_location: GLint,
+    to: Option<GLuint>,
+    to_location: GLint,
+    gl_type: GLenum,
+) -> bool {
+    // Large enough for a mat4, read back column major like it's uploaded.
+    macro_rules! vector {
+        ($scalar:ty, $get:path, $upload:ident, $program_upload:ident) => {{
+            let mut values = [<$scalar>::default(); 16];
+            $get(from, from_location, values.as_mut_ptr());
+            upload!(
+                to,
+                $upload,
+                $program_upload,
+                to_location,
+                1,
+                values.as_ptr()
+            )
+        }};
+    }
+    macro_rules! matrix {
+        ($upload:ident, $program_upload:ident) => {
+            matrix!(f32, gl::GetUniformfv, $upload, $program_upload)
+        };
+        ($scalar:ty, $get:path, $upload:ident, $program_upload:ident) => {{
+            let mut values = [<$scalar>::default(); 16];
+            $get(from, from_location, values.as_mut_ptr());
+            upload!(
+                to,
+                $upload,
+                $program_upload,
+                to_location,
+                1,
+                gl::FALSE,
+                values.as_ptr()
+            )
+        }};
+    }
+    macro_rules! double_matrix {
+        ($upload:ident, $program_upload:ident) => {
+            matrix!(f64, gl::GetUniformdv, $upload, $program_upload)
+        };
+    }
+
+    match gl_type {
+        gl::FLOAT => vector!(f32, gl::GetUniformfv, Uniform1fv, ProgramUniform1fv),
+        gl::FLOAT_VEC2 => vector!(f32, gl::GetUniformfv, Uniform2fv, ProgramUniform2fv),
+        gl::FLOAT_VEC3 => vector!(f32, gl::GetUniformfv, Uniform3fv, ProgramUniform3fv),
+        gl::FLOAT_VEC4 => vector!(f32, gl::GetUniformfv, Uniform4fv, ProgramUniform4fv),
+        // Bools read back as 0 or 1.
+        gl::INT | gl::BOOL => vector!(i32, gl::GetUniformiv, Uniform1iv, ProgramUniform1iv),
+        gl::INT_VEC2 | gl::BOOL_VEC2 => {
+            vector!(i32, gl::GetUniformiv, Uniform2iv, ProgramUniform2iv)
+        }
+        gl::INT_VEC3 | gl::BOOL_VEC3 => {
+            vector!(i32, gl::GetUniformiv, Uniform3iv, ProgramUniform3iv)
+        }
+        gl::INT_VEC4 | gl::BOOL_VEC4 => {
+            vector!(i32, gl::GetUniformiv, Uniform4iv, ProgramUniform4iv)
+        }
+        gl::UNSIGNED_INT => vector!(u32, gl::GetUniformuiv, Uniform1uiv, ProgramUniform1uiv),
+        gl::UNSIGNED_INT_VEC2 => vector!(u32, gl::GetUniformuiv, Uniform2uiv, ProgramUniform2uiv),
+        gl::UNSIGNED_INT_VEC3 => vector!(u32, gl::GetUniformuiv, Uniform3uiv, ProgramUniform3uiv),
+        gl::UNSIGNED_INT_VEC4 => vector!(u32, gl::GetUniformuiv, Uniform4uiv, ProgramUniform4uiv),
+        gl::FLOAT_MAT2 => matrix!(UniformMatrix2fv, ProgramUniformMatrix2fv),
+        gl::FLOAT_MAT3 => matrix!(UniformMatrix3fv, ProgramUniformMatrix3fv),
+        gl::FLOAT_MAT4 => matrix!(UniformMatrix4fv, ProgramUniformMatrix4fv),
+        gl::FLOAT_MAT2x3 => matrix!(UniformMatrix2x3fv, ProgramUniformMatrix2x3fv),
+        gl::FLOAT_MAT2x4 => matrix!(UniformMatrix2x4fv, ProgramUniformMatrix2x4fv),
+        gl::FLOAT_MAT3x2 => matrix!(UniformMatrix3x2fv, ProgramUniformMatrix3x2fv),
+        gl::FLOAT_MAT3x4 => matrix!(UniformMatrix3x4fv, ProgramUniformMatrix3x4fv),
+        gl::FLOAT_MAT4x2 => matrix!(UniformMatrix4x2fv, ProgramUniformMatrix4x2fv),
+        gl::FLOAT_MAT4x3 => matrix!(UniformMatrix4x3fv, ProgramUniformMatrix4x3fv),
+        gl::DOUBLE => vector!(f64, gl::GetUniformdv, Uniform1dv, ProgramUniform1dv),
+        gl::DOUBLE_VEC2 => vector!(f64, gl::GetUniformdv, Uniform2dv, ProgramUniform2dv),
+        gl::DOUBLE_VEC3 => vector!(f64, gl::GetUniformdv, Uniform3dv, ProgramUniform3dv),
+        gl::DOUBLE_VEC4 => vector!(f64, gl::GetUniformdv, Uniform4dv, ProgramUniform4dv),
+        gl::DOUBLE_MAT2 => double_matrix!(UniformMatrix2dv, ProgramUniformMatrix2dv),
+        gl::DOUBLE_MAT3 => double_matrix!(UniformMatrix3dv, ProgramUniformMatrix3dv),
+        gl::DOUBLE_MAT4 => double_matrix!(UniformMatrix4dv, ProgramUniformMatrix4dv),
+        gl::DOUBLE_MAT2x3 => double_matrix!(UniformMatrix2x3dv, ProgramUniformMatrix2x3dv),
+        gl::DOUBLE_MAT2x4 => double_matrix!(UniformMatrix2x4dv, ProgramUniformMatrix2x4dv),
+        gl::DOUBLE_MAT3x2 => double_matrix!(UniformMatrix3x2dv, ProgramUniformMatrix3x2dv),
+        gl::DOUBLE_MAT3x4 => double_matrix!(UniformMatrix3x4dv, ProgramUniformMatrix3x4dv),
+        gl::DOUBLE_MAT4x2 => double_matrix!(UniformMatrix4x2dv, ProgramUniformMatrix4x2dv),
+        gl::DOUBLE_MAT4x3 => double_matrix!(UniformMatrix4x3dv, ProgramUniformMatrix4x3dv),
+        gl_type if reflection::is_sampler(gl_type) => {
+            vector!(i32, gl::GetUniformiv, Uniform1iv, ProgramUniform1iv)
+        }
+        _ => return false,
+    }
+
+    true
+}
@@ -0,0 +1,218 @@
+use std::{mem, os::raw::c_void};
+
+use gl::types::{GLenum, GLint, GLsizei, GLuint};
+
+pub use engine_derive::Vertex;
+
+use super::buffer::{Buffer, Pod};
+use super::context::GlContext;
+use super::gl_wrapper::Vao;
+
+/// Describes how a struct is laid out in a vertex buffer. Usually derived:
+///
+/// ```ignore
+/// #[derive(Clone, Copy, Pod, Zeroable, Vertex)]
+/// #[repr(C)]
+/// struct TexturedVertex {
+///     position: [f32; 3],
+///     #[vertex(normalized)]
+///     color: [u8; 4],
+///     uv: [f32; 2],
+/// }
+/// ```
+pub trait Vertex: Pod {
+    fn attributes() -> Vec<VertexAttributeLayout>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VertexAttributeLayout {
+    pub location: GLuint,
+    pub components: GLint,
+    pub r#type: GLenum,
+    pub normalized: bool,
+    /// Byte offset of the field inside the vertex.
+    pub offset: usize,
+}
+
+impl VertexAttributeLayout {
+    pub fn of<T: VertexComponent>(location: GLuint, normalized: bool, offset: usize) -> Self {
+        VertexAttributeLayout {
+            location,
+            components: T::COMPONENTS,
+            r#type: T::TYPE,
+            normalized,
+            offset,
+        }
+    }
+
+    /// Integer fields that aren't normalized reach the shader as `int`/`uint`
+    /// and need `glVertexAttribIPointer`.
+    pub fn is_integer(&self) -> bool {
+        !self.normalized && self.r#type != gl::FLOAT && self.r#type != gl::DOUBLE
+    }
+
+    /// Sets up the attribute on the bound `Vao` for the bound array buffer.
+    pub fn apply(&self, stride: GLsizei) {
+        let pointer = self.offset as *const c_void;
+
+        unsafe {
+            if self.is_integer() {
+                gl::VertexAttribIPointer(
+                    self.location,
+                    self.components,
+                    self.r#type,
+                    stride,
+                    pointer,
+                );
+            } else {
+                let normalized = if self.normalized { gl::TRUE } else { gl::FALSE };
+                gl::VertexAttribPointer(
+                    self.location,
+                    self.components,
+                    self.r#type,
+                    normalized,
+                    stride,
+                    pointer,
+                );
+            }
+            gl::EnableVertexAttribArray(self.location);
+        }
+    }
+}
+
+/// Field types usable in a `Vertex`: GL scalars and arrays of one to four of
+/// them. glm vectors aren't `Pod`, store a `glm::Vec3` as `[f32; 3]` with
+/// `*v.as_array()` and read it back with `*glm::Vec3::from_array(&a)`.
+pub trait VertexComponent {
+    const COMPONENTS: GLint;
+    const TYPE: GLenum;
+}
+
+macro_rules! vertex_components {
+    ($($scalar:ty => $gl_type:expr),* $(,)?) => {
+        $(
+            impl VertexComponent for $scalar {
+                const COMPONENTS: GLint = 1;
+                const TYPE: GLenum = $gl_type;
+            }
+
+            impl<const N: usize> VertexComponent for [$scalar; N] {
+                const COMPONENTS: GLint = {
+                    assert!(N >= 1 && N <= 4, "vertex attributes have 1 to 4 components");
+                    N as GLint
+                };
+                const TYPE: GLenum = $gl_type;
+            }
+        )*
+    };
+}
+
+vertex_components! {
+    f32 => gl::FLOAT,
+    f64 => gl::DOUBLE,
+    i8 => gl::BYTE,
+    u8 => gl::UNSIGNED_BYTE,
+    i16 => gl::SHORT,
+    u16 => gl::UNSIGNED_SHORT,
+    i32 => gl::INT,
+    u32 => gl::UNSIGNED_INT,
+}
+
+/// An array buffer holding `T`s, with the attributes of `T` configured on the
+/// `Vao` it was created with.
+pub struct VertexBuffer<T: Vertex> {
+    buffer: Buffer<T>,
+}
+
+impl<T: Vertex> VertexBuffer<T> {
+    pub fn new(context: &GlContext, vao: &Vao, vertices: &[T], usage: GLenum) -> VertexBuffer<T> {
+        vao.bind();
+        let buffer = Buffer::with_data(context, gl::ARRAY_BUFFER, vertices, usage);
+
+        let stride = mem::size_of::<T>() as GLsizei;
+        for attribute in T::attributes() {
+            attribute.apply(stride);
+        }
+
+        VertexBuffer { buffer }
+    }
+
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    pub fn buffer(&self) -> &Buffer<T> {
+        &self.buffer
+    }
+
+    /// For updates, mapping and streaming. The attribute setup stays valid
+    /// however the storage gets reallocated.
+    pub fn buffer_mut(&mut self) -> &mut Buffer<T> {
+        &mut self.buffer
+    }
+
+    pub fn into_buffer(self) -> Buffer<T> {
+        self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytemuck::Zeroable;
+
+    use super::*;
+
+    #[derive(Clone, Copy, Pod, Zeroable, Vertex)]
+    #[repr(C)]
+    struct TestVertex {
+        position: [f32; 3],
+        #[vertex(skip)]
+        _padding: f32,
+        #[vertex(normalized)]
+        color: [u8; 4],
+        #[vertex(location = 5)]
+        id: u32,
+        uv: [u16; 2],
+    }
+
+    #[test]
+    fn derived_attributes() {
+        assert_eq!(
+            TestVertex::attributes(),
+            [
+                VertexAttributeLayout {
+                    location: 0,
+                    components: 3,
+                    r#type: gl::FLOAT,
+                    normalized: false,
+                    offset: mem::offset_of!(TestVertex, position),
+                },
+                VertexAttributeLayout {
+                    location: 1,
+                    components: 4,
+                    r#type: gl::UNSIGNED_BYTE,
+                    normalized: true,
+                    offset: mem::offset_of!(TestVertex, color),
+                },
+                VertexAttributeLayout {
+                    location: 5,
+                    components: 1,
+                    r#type: gl::UNSIGNED_INT,
+                    normalized: false,
+                    offset: mem::offset_of!(TestVertex, id),
+                },
+                VertexAttributeLayout {
+                    location: 6,
+                    components: 2,
+                    r#type: gl::UNSIGNED_SHORT,
+                    normalized: false,
+                    offset: mem::offset_of!(TestVertex, uv),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use image::RgbaImage;
+
+#[cfg(target_os = "linux")]
+use crate::egl::EglContext;
+use crate::errors::{Errors, WindowErrors};
+use crate::graphics::context::GlContext;
+use crate::graphics::framebuffer::Framebuffer;
+use crate::logger;
+use crate::window::{ContextApi, GlProfile, Window};
+
+/// A GL context that renders into an offscreen framebuffer instead of a
+/// visible window, for tests and CI. Tries a hidden native window first,
+/// then EGL and OSMesa through GLFW. When GLFW can't even start because
+/// there's no display, Linux falls back to a surfaceless EGL context, which
+/// with `LIBGL_ALWAYS_SOFTWARE=1` runs on Mesa's software rasterizer.
+pub struct HeadlessContext {
+    framebuffer: Framebuffer,
+    context: GlContext,
+    window: Option<Window>,
+    /// Owns the context when there's no window.
+    #[cfg(target_os = "linux")]
+    egl: Option<EglContext>,
+}
+
+impl HeadlessContext {
+    pub fn new(width: u32, height: u32) -> Result<HeadlessContext, Errors> {
+        #[cfg(target_os = "linux")]
+        let mut egl = None;
+
+        let (context, window) = match create_hidden_window(width, height) {
+            Ok(mut window) => (window.init_gl()?, Some(window)),
+            #[cfg(target_os = "linux")]
+            Err(error) => {
+                logger::warn!("No headless window ({}), trying surfaceless EGL", error);
+                let context = egl.insert(EglContext::new(3, 3)?).context();
+                (context, None)
+            }
+            #[cfg(not(target_os = "linux"))]
+            Err(error) => return Err(error),
+        };
+
+        let framebuffer = Framebuffer::new(&context, width as i32, height as i32)?;
+        framebuffer.bind();
+
+        Ok(HeadlessContext {
+            framebuffer,
+            context,
+            window,
+            #[cfg(target_os = "linux")]
+            egl,
+        })
+    }
+
+    /// The hidden window the context belongs to, `None` for a surfaceless
+    /// EGL context.
+    pub fn window(&mut self) -> Option<&mut Window> {
+        self.window.as_mut()
+    }
+
+    pub fn context(&self) -> &GlContext {
+        &self.context
+    }
+
+    /// Makes the context current again, after rendering with a window on
+    /// this thread.
+    pub fn make_current(&mut self) {
+        match &mut self.window {
+            Some(window) => window.make_current(),
+            #[cfg(target_os = "linux")]
+            None => {
+                if let Some(egl) = &self.egl {
+                    if !egl.make_current() {
+                        logger::error!("Couldn't make the surfaceless EGL context current");
+                    }
+                }
+            }
+            #[cfg(not(target_os = "linux"))]
+            None => {}
+        }
+    }
+
+    pub fn framebuffer(&self) -> &Framebuffer {
+        &self.framebuffer
+    }
+
+    /// Finishes pending draws and returns the framebuffer contents as RGBA8,
+    /// bottom row first.
+    pub fn read_pixels(&self) -> Vec<u8> {
+        unsafe {
+            gl::Finish();
+        }
+
+        self.framebuffer.read_pixels()
+    }
+
+    pub fn read_image(&self) -> RgbaImage {
+        self.framebuffer.read_image()
+    }
+}
+
+fn create_hidden_window(width: u32, height: u32) -> Result<Window, Errors> {
+    let mut last_error = None;
+
+    for api in [ContextApi::Native, ContextApi::Egl, ContextApi::OsMesa] {
+        let window = Window::builder(width, height, "headless")
+            .visible(false)
+            .resizable(false)
+            .vsync(false)
+            .context_version(3, 3)
+            .profile(GlProfile::Core)
+            .context_api(api)
+            .build();
+
+        match window {
+            Ok(window) => return Ok(window),
+            // Every api goes through the same init, no display means none works.
+            Err(error @ Errors::WindowError(WindowErrors::InitFailed(_))) => return Err(error),
+            Err(error) => {
+                logger::warn!("Headless context with {:?} failed: {}", api, error);
+                last_error = Some(error);
+            }
+        }
+    }
+
+    Err(last_error.expect("tried at least one context api"))
+}
@@ -0,0 +1,268 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{Errors, InputErrors};
+
+use super::events::{GamepadAxis, GamepadButton, Key, MouseButton};
+use super::state::Input;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Binding {
+    Key(Key),
+    Mouse(MouseButton),
+    Gamepad(GamepadButton),
+}
+
+impl Binding {
+    fn is_down(&self, input: &Input) -> bool {
+        match *self {
+            Binding::Key(key) => input.is_key_down(key),
+            Binding::Mouse(button) => input.is_mouse_button_down(button),
+            Binding::Gamepad(button) => input.is_gamepad_button_down(button),
+        }
+    }
+
+    fn was_pressed(&self, input: &Input) -> bool {
+        match *self {
+            Binding::Key(key) => input.was_key_pressed(key),
+            Binding::Mouse(button) => input.was_mouse_button_pressed(button),
+            Binding::Gamepad(button) => input.was_gamepad_button_pressed(button),
+        }
+    }
+
+    fn was_released(&self, input: &Input) -> bool {
+        match *self {
+            Binding::Key(key) => input.was_key_released(key),
+            Binding::Mouse(button) => input.was_mouse_button_released(button),
+            Binding::Gamepad(button) => input.was_gamepad_button_released(button),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AxisBinding {
+    /// Two digital inputs driving the axis to -1.0 and 1.0.
+    Buttons {
+        negative: Binding,
+        positive: Binding,
+    },
+    Gamepad {
+        axis: GamepadAxis,
+        #[serde(default)]
+        inverted: bool,
+    },
+}
+
+impl AxisBinding {
+    fn value(&self, input: &Input) -> f32 {
+        match *self {
+            AxisBinding::Buttons { negative, positive } => {
+                positive.is_down(input) as i32 as f32 - negative.is_down(input) as i32 as f32
+            }
+            AxisBinding::Gamepad { axis, inverted } => match inverted {
+                true => -input.gamepad_axis(axis),
+                false => input.gamepad_axis(axis),
+            },
+        }
+    }
+}
+
+/// Named actions and axes mapped to physical inputs, so game code asks for
+/// "jump" instead of a specific key. Serialized as TOML for player configs.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct InputMap {
+    #[serde(default)]
+    actions: BTreeMap<String, Vec<Binding>>,
+    #[serde(default)]
+    axes: BTreeMap<String, Vec<AxisBinding>>,
+}
+
+impl InputMap {
+    pub fn new() -> InputMap {
+        InputMap::default()
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<InputMap, Errors> {
+        let source = fs::read_to_string(path).map_err(InputErrors::Io)?;
+
+        InputMap::from_toml(&source)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Errors> {
+        fs::write(path, self.to_toml()?).map_err(InputErrors::Io)?;
+
+        Ok(())
+    }
+
+    pub fn from_toml(source: &str) -> Result<InputMap, Errors> {
+        Ok(toml::from_str(source).map_err(InputErrors::Deserialize)?)
+    }
+
+    pub fn to_toml(&self) -> Result<String, Errors> {
+        Ok(toml::to_string_pretty(self).map_err(InputErrors::Serialize)?)
+    }
+
+    pub fn bind_action(&mut self, action: &str, binding: Binding) {
+        let bindings = self.actions.entry(action.to_owned()).or_default();
+
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+
+    /// Replaces every binding of `action`, used when the player remaps a control.
+    pub fn rebind_action(&mut self, action: &str, bindings: Vec<Binding>) {
+        self.actions.insert(action.to_owned(), bindings);
+    }
+
+    pub fn unbind_action(&mut self, action: &str, binding: Binding) {
+        if let Some(bindings) = self.actions.get_mut(action) {
+            bindings.retain(|b| *b != binding);
+        }
+    }
+
+    pub fn action_bindings(&self, action: &str) -> &[Binding] {
+        self.actions.get(action).map_or(&[], |b| b.as_slice())
+    }
+
+    pub fn bind_axis(&mut self, axis: &str, binding: AxisBinding) {
+        let bindings = self.axes.entry(axis.to_owned()).or_default();
+
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+
+    pub fn rebind_axis(&mut self, axis: &str, bindings: Vec<AxisBinding>) {
+        self.axes.insert(axis.to_owned(), bindings);
+    }
+
+    pub fn axis_bindings(&self, axis: &str) -> &[AxisBinding] {
+        self.axes.get(axis).map_or(&[], |b| b.as_slice())
+    }
+
+    pub fn is_action_down(&self, input: &Input, action: &str) -> bool {
+        self.action_bindings(action)
+            .iter()
+            .any(|b| b.is_down(input))
+    }
+
+    pub fn was_action_pressed(&self, input: &Input, action: &str) -> bool {
+        self.action_bindings(action)
+            .iter()
+            .any(|b| b.was_pressed(input))
+    }
+
+    pub fn was_action_released(&self, input: &Input, action: &str) -> bool {
+        self.action_bindings(action)
+            .iter()
+            .any(|b| b.was_released(input))
+    }
+
+    /// Sum of every binding of `axis`, clamped to `-1.0..=1.0`.
+    pub fn axis(&self, input: &Input, axis: &str) -> f32 {
+        self.axis_bindings(axis)
+            .iter()
+            .map(|b| b.value(input))
+            .sum::<f32>()
+            .clamp(-1.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::events::{InputEvent, Modifiers};
+
+    fn press(input: &mut Input, key: Key) {
+        input.handle_event(InputEvent::KeyPressed {
+            key,
+            scancode: 0,
+            modifiers: Modifiers::default(),
+            repeat: false,
+        });
+    }
+
+    fn move_axis(input: &mut Input, axis: GamepadAxis, value: f32) {
+        input.handle_event(InputEvent::GamepadAxisMoved {
+            gamepad: 0,
+            axis,
+            value,
+        });
+    }
+
+    fn movement_map() -> InputMap {
+        let mut map = InputMap::new();
+        map.bind_axis(
+            "move_x",
+            AxisBinding::Buttons {
+                negative: Binding::Key(Key::A),
+                positive: Binding::Key(Key::D),
+            },
+        );
+        map.bind_axis(
+            "move_x",
+            AxisBinding::Gamepad {
+                axis: GamepadAxis::LeftX,
+                inverted: false,
+            },
+        );
+        map.bind_axis(
+            "move_y",
+            AxisBinding::Gamepad {
+                axis: GamepadAxis::LeftY,
+                inverted: true,
+            },
+        );
+
+        map
+    }
+
+    #[test]
+    fn button_axes() {
+        let map = movement_map();
+        let mut input = Input::new();
+        assert_eq!(map.axis(&input, "move_x"), 0.0);
+
+        press(&mut input, Key::A);
+        assert_eq!(map.axis(&input, "move_x"), -1.0);
+
+        press(&mut input, Key::D);
+        assert_eq!(map.axis(&input, "move_x"), 0.0);
+    }
+
+    #[test]
+    fn bindings_add_up_and_clamp() {
+        let map = movement_map();
+        let mut input = Input::new();
+
+        move_axis(&mut input, GamepadAxis::LeftX, 0.25);
+        assert_eq!(map.axis(&input, "move_x"), 0.25);
+
+        press(&mut input, Key::A);
+        assert_eq!(map.axis(&input, "move_x"), -0.75);
+
+        move_axis(&mut input, GamepadAxis::LeftX, -0.5);
+        assert_eq!(map.axis(&input, "move_x"), -1.0);
+    }
+
+    #[test]
+    fn inverted_gamepad_axis() {
+        let map = movement_map();
+        let mut input = Input::new();
+
+        move_axis(&mut input, GamepadAxis::LeftY, 0.5);
+        assert_eq!(map.axis(&input, "move_y"), -0.5);
+    }
+
+    #[test]
+    fn unbound_axis_is_zero() {
+        let mut input = Input::new();
+        move_axis(&mut input, GamepadAxis::LeftX, 1.0);
+
+        assert_eq!(movement_map().axis(&input, "look_x"), 0.0);
+    }
+}
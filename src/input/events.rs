@@ -0,0 +1,272 @@
+use serde::{Deserialize, Serialize};
+
+macro_rules! keys {
+    ($($name:ident),* $(,)?) => {
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+        pub enum Key {
+            $($name,)*
+            Unknown,
+        }
+
+        impl From<glfw::Key> for Key {
+            fn from(key: glfw::Key) -> Key {
+                match key {
+                    $(glfw::Key::$name => Key::$name,)*
+                    glfw::Key::Unknown => Key::Unknown,
+                }
+            }
+        }
+    };
+}
+
+keys! {
+    Space, Apostrophe, Comma, Minus, Period, Slash, Num0, Num1, Num2, Num3,
+    Num4, Num5, Num6, Num7, Num8, Num9, Semicolon, Equal, A, B,
+    C, D, E, F, G, H, I, J, K, L,
+    M, N, O, P, Q, R, S, T, U, V,
+    W, X, Y, Z, LeftBracket, Backslash, RightBracket, GraveAccent, World1, World2,
+    Escape, Enter, Tab, Backspace, Insert, Delete, Right, Left, Down, Up,
+    PageUp, PageDown, Home, End, CapsLock, ScrollLock, NumLock, PrintScreen, Pause, F1,
+    F2, F3, F4, F5, F6, F7, F8, F9, F10, F11,
+    F12, F13, F14, F15, F16, F17, F18, F19, F20, F21,
+    F22, F23, F24, F25, Kp0, Kp1, Kp2, Kp3, Kp4, Kp5,
+    Kp6, Kp7, Kp8, Kp9, KpDecimal, KpDivide, KpMultiply, KpSubtract, KpAdd, KpEnter,
+    KpEqual, LeftShift, LeftControl, LeftAlt, LeftSuper, RightShift, RightControl, RightAlt, RightSuper, Menu,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+    Button4,
+    Button5,
+    Button6,
+    Button7,
+    Button8,
+}
+
+impl From<glfw::MouseButton> for MouseButton {
+    fn from(button: glfw::MouseButton) -> MouseButton {
+        match button {
+            glfw::MouseButton::Button1 => MouseButton::Left,
+            glfw::MouseButton::Button2 => MouseButton::Right,
+            glfw::MouseButton::Button3 => MouseButton::Middle,
+            glfw::MouseButton::Button4 => MouseButton::Button4,
+            glfw::MouseButton::Button5 => MouseButton::Button5,
+            glfw::MouseButton::Button6 => MouseButton::Button6,
+            glfw::MouseButton::Button7 => MouseButton::Button7,
+            glfw::MouseButton::Button8 => MouseButton::Button8,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GamepadButton {
+    A,
+    B,
+    X,
+    Y,
+    LeftBumper,
+    RightBumper,
+    Back,
+    Start,
+    Guide,
+    LeftThumb,
+    RightThumb,
+    DpadUp,
+    DpadRight,
+    DpadDown,
+    DpadLeft,
+}
+
+impl GamepadButton {
+    pub const ALL: [GamepadButton; 15] = [
+        GamepadButton::A,
+        GamepadButton::B,
+        GamepadButton::X,
+        GamepadButton::Y,
+        GamepadButton::LeftBumper,
+        GamepadButton::RightBumper,
+        GamepadButton::Back,
+        GamepadButton::Start,
+        GamepadButton::Guide,
+        GamepadButton::LeftThumb,
+        GamepadButton::RightThumb,
+        GamepadButton::DpadUp,
+        GamepadButton::DpadRight,
+        GamepadButton::DpadDown,
+        GamepadButton::DpadLeft,
+    ];
+}
+
+impl From<GamepadButton> for glfw::GamepadButton {
+    fn from(button: GamepadButton) -> glfw::GamepadButton {
+        match button {
+            GamepadButton::A => glfw::GamepadButton::ButtonA,
+            GamepadButton::B => glfw::GamepadButton::ButtonB,
+            GamepadButton::X => glfw::GamepadButton::ButtonX,
+            GamepadButton::Y => glfw::GamepadButton::ButtonY,
+            GamepadButton::LeftBumper => glfw::GamepadButton::ButtonLeftBumper,
+            GamepadButton::RightBumper => glfw::GamepadButton::ButtonRightBumper,
+            GamepadButton::Back => glfw::GamepadButton::ButtonBack,
+            GamepadButton::Start => glfw::GamepadButton::ButtonStart,
+            GamepadButton::Guide => glfw::GamepadButton::ButtonGuide,
+            GamepadButton::LeftThumb => glfw::GamepadButton::ButtonLeftThumb,
+            GamepadButton::RightThumb => glfw::GamepadButton::ButtonRightThumb,
+            GamepadButton::DpadUp => glfw::GamepadButton::ButtonDpadUp,
+            GamepadButton::DpadRight => glfw::GamepadButton::ButtonDpadRight,
+            GamepadButton::DpadDown => glfw::GamepadButton::ButtonDpadDown,
+            GamepadButton::DpadLeft => glfw::GamepadButton::ButtonDpadLeft,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GamepadAxis {
+    LeftX,
+    LeftY,
+    RightX,
+    RightY,
+    LeftTrigger,
+    RightTrigger,
+}
+
+impl GamepadAxis {
+    pub const ALL: [GamepadAxis; 6] = [
+        GamepadAxis::LeftX,
+        GamepadAxis::LeftY,
+        GamepadAxis::RightX,
+        GamepadAxis::RightY,
+        GamepadAxis::LeftTrigger,
+        GamepadAxis::RightTrigger,
+    ];
+}
+
+impl From<GamepadAxis> for glfw::GamepadAxis {
+    fn from(axis: GamepadAxis) -> glfw::GamepadAxis {
+        match axis {
+            GamepadAxis::LeftX => glfw::GamepadAxis::AxisLeftX,
+            GamepadAxis::LeftY => glfw::GamepadAxis::AxisLeftY,
+            GamepadAxis::RightX => glfw::GamepadAxis::AxisRightX,
+            GamepadAxis::RightY => glfw::GamepadAxis::AxisRightY,
+            GamepadAxis::LeftTrigger => glfw::GamepadAxis::AxisLeftTrigger,
+            GamepadAxis::RightTrigger => glfw::GamepadAxis::AxisRightTrigger,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub control: bool,
+    pub alt: bool,
+    pub super_key: bool,
+}
+
+impl From<glfw::Modifiers> for Modifiers {
+    fn from(modifiers: glfw::Modifiers) -> Modifiers {
+        Modifiers {
+            shift: modifiers.contains(glfw::Modifiers::Shift),
+            control: modifiers.contains(glfw::Modifiers::Control),
+            alt: modifiers.contains(glfw::Modifiers::Alt),
+            super_key: modifiers.contains(glfw::Modifiers::Super),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum InputEvent {
+    KeyPressed {
+        key: Key,
+        scancode: i32,
+        modifiers: Modifiers,
+        repeat: bool,
+    },
+    KeyReleased {
+        key: Key,
+        scancode: i32,
+        modifiers: Modifiers,
+    },
+    Char(char),
+    MouseButtonPressed {
+        button: MouseButton,
+        modifiers: Modifiers,
+    },
+    MouseButtonReleased {
+        button: MouseButton,
+        modifiers: Modifiers,
+    },
+    CursorMoved {
+        x: f64,
+        y: f64,
+    },
+    CursorEntered(bool),
+    Scrolled {
+        x: f64,
+        y: f64,
+    },
+    Focused(bool),
+    GamepadConnected {
+        gamepad: usize,
+        name: String,
+    },
+    GamepadDisconnected {
+        gamepad: usize,
+    },
+    GamepadButtonPressed {
+        gamepad: usize,
+        button: GamepadButton,
+    },
+    GamepadButtonReleased {
+        gamepad: usize,
+        button: GamepadButton,
+    },
+    GamepadAxisMoved {
+        gamepad: usize,
+        axis: GamepadAxis,
+        value: f32,
+    },
+}
+
+impl InputEvent {
+    /// Translates the glfw events the input system cares about, anything
+    /// else (resize, close, ...) is left to the window.
+    pub(crate) fn from_glfw(event: &glfw::WindowEvent) -> Option<InputEvent> {
+        let event = match *event {
+            glfw::WindowEvent::Key(key, scancode, glfw::Action::Release, modifiers) => {
+                InputEvent::KeyReleased {
+                    key: key.into(),
+                    scancode,
+                    modifiers: modifiers.into(),
+                }
+            }
+            glfw::WindowEvent::Key(key, scancode, action, modifiers) => InputEvent::KeyPressed {
+                key: key.into(),
+                scancode,
+                modifiers: modifiers.into(),
+                repeat: action == glfw::Action::Repeat,
+            },
+            glfw::WindowEvent::Char(c) => InputEvent::Char(c),
+            glfw::WindowEvent::MouseButton(button, glfw::Action::Release, modifiers) => {
+                InputEvent::MouseButtonReleased {
+                    button: button.into(),
+                    modifiers: modifiers.into(),
+                }
+            }
+            glfw::WindowEvent::MouseButton(button, _, modifiers) => {
+                InputEvent::MouseButtonPressed {
+                    button: button.into(),
+                    modifiers: modifiers.into(),
+                }
+            }
+            glfw::WindowEvent::CursorPos(x, y) => InputEvent::CursorMoved { x, y },
+            glfw::WindowEvent::CursorEnter(entered) => InputEvent::CursorEntered(entered),
+            glfw::WindowEvent::Scroll(x, y) => InputEvent::Scrolled { x, y },
+            glfw::WindowEvent::Focus(focused) => InputEvent::Focused(focused),
+            _ => return None,
+        };
+
+        Some(event)
+    }
+}
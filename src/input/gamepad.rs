@@ -0,0 +1,208 @@
+use std::{fs, path::Path};
+
+use glfw::Glfw;
+
+use crate::errors::{Errors, InputErrors};
+
+use super::events::{GamepadAxis, GamepadButton, InputEvent};
+
+const MAX_GAMEPADS: usize = 16;
+
+#[derive(Clone, Copy, Default)]
+struct GamepadSnapshot {
+    buttons: [bool; GamepadButton::ALL.len()],
+    axes: [f32; GamepadAxis::ALL.len()],
+}
+
+/// Polls the glfw joysticks that have a gamepad mapping and turns their
+/// state changes into `InputEvent`s, with dead zones already applied.
+pub struct Gamepads {
+    glfw: Glfw,
+    connected: [Option<GamepadSnapshot>; MAX_GAMEPADS],
+    stick_dead_zone: f32,
+    trigger_dead_zone: f32,
+}
+
+impl Gamepads {
+    pub(crate) fn new(glfw: Glfw) -> Gamepads {
+        Gamepads {
+            glfw,
+            connected: [None; MAX_GAMEPADS],
+            stick_dead_zone: 0.15,
+            trigger_dead_zone: 0.05,
+        }
+    }
+
+    /// Radial dead zone applied to both sticks, in `0.0..1.0`.
+    pub fn set_stick_dead_zone(&mut self, dead_zone: f32) {
+        self.stick_dead_zone = dead_zone.clamp(0.0, 0.99);
+    }
+
+    pub fn set_trigger_dead_zone(&mut self, dead_zone: f32) {
+        self.trigger_dead_zone = dead_zone.clamp(0.0, 0.99);
+    }
+
+    /// Loads SDL style mappings such as the community `gamecontrollerdb.txt`,
+    /// adding support for controllers glfw doesn't know out of the box.
+    pub fn load_mappings(&self, path: impl AsRef<Path>) -> Result<(), Errors> {
+        let mappings = fs::read_to_string(path).map_err(InputErrors::Io)?;
+
+        self.load_mappings_from_str(&mappings)
+    }
+
+    pub fn load_mappings_from_str(&self, mappings: &str) -> Result<(), Errors> {
+        if !self.glfw.update_gamepad_mappings(mappings) {
+            return Err(InputErrors::InvalidGamepadMappings.into());
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn poll(&mut self, events: &mut Vec<InputEvent>) {
+        for (gamepad, previous) in self.connected.iter_mut().enumerate() {
+            let joystick = match glfw::JoystickId::from_i32(gamepad as i32) {
+                Some(id) => self.glfw.get_joystick(id),
+                None => continue,
+            };
+
+            let state = match joystick.is_present() {
+                true => joystick.get_gamepad_state(),
+                false => None,
+            };
+
+            let state = match (state, previous.as_ref()) {
+                (Some(state), _) => state,
+                (None, Some(last)) => {
+                    for (i, &button) in GamepadButton::ALL.iter().enumerate() {
+                        if last.buttons[i] {
+                            events.push(InputEvent::GamepadButtonReleased { gamepad, button });
+                        }
+                    }
+                    events.push(InputEvent::GamepadDisconnected { gamepad });
+                    *previous = None;
+                    continue;
+                }
+                (None, None) => continue,
+            };
+
+            let last = match previous {
+                Some(last) => *last,
+                None => {
+                    events.push(InputEvent::GamepadConnected {
+                        gamepad,
+                        name: joystick.get_gamepad_name().unwrap_or_default(),
+                    });
+                    GamepadSnapshot::default()
+                }
+            };
+
+            let mut current = GamepadSnapshot::default();
+
+            for (i, &button) in GamepadButton::ALL.iter().enumerate() {
+                current.buttons[i] = state.get_button_state(button.into()) != glfw::Action::Release;
+
+                match (last.buttons[i], current.buttons[i]) {
+                    (false, true) => {
+                        events.push(InputEvent::GamepadButtonPressed { gamepad, button })
+                    }
+                    (true, false) => {
+                        events.push(InputEvent::GamepadButtonReleased { gamepad, button })
+                    }
+                    _ => {}
+                }
+            }
+
+            let raw = GamepadAxis::ALL.map(|axis| state.get_axis(axis.into()));
+            let (left_x, left_y) = apply_stick_dead_zone(raw[0], raw[1], self.stick_dead_zone);
+            let (right_x, right_y) = apply_stick_dead_zone(raw[2], raw[3], self.stick_dead_zone);
+
+            current.axes = [
+                left_x,
+                left_y,
+                right_x,
+                right_y,
+                apply_trigger_dead_zone(raw[4], self.trigger_dead_zone),
+                apply_trigger_dead_zone(raw[5], self.trigger_dead_zone),
+            ];
+
+            for (i, &axis) in GamepadAxis::ALL.iter().enumerate() {
+                if current.axes[i] != last.axes[i] {
+                    events.push(InputEvent::GamepadAxisMoved {
+                        gamepad,
+                        axis,
+                        value: current.axes[i],
+                    });
+                }
+            }
+
+            *previous = Some(current);
+        }
+    }
+}
+
+/// Radial dead zone, rescaled so the output still covers the full range.
+fn apply_stick_dead_zone(x: f32, y: f32, dead_zone: f32) -> (f32, f32) {
+    let magnitude = (x * x + y * y).sqrt();
+
+    if magnitude <= dead_zone {
+        return (0.0, 0.0);
+    }
+
+    let scale = ((magnitude.min(1.0) - dead_zone) / (1.0 - dead_zone)) / magnitude;
+    (x * scale, y * scale)
+}
+
+/// glfw reports triggers in `-1.0..=1.0` with -1 at rest, we expose `0.0..=1.0`.
+fn apply_trigger_dead_zone(value: f32, dead_zone: f32) -> f32 {
+    let value = ((value + 1.0) * 0.5).clamp(0.0, 1.0);
+
+    if value <= dead_zone {
+        return 0.0;
+    }
+
+    (value - dead_zone) / (1.0 - dead_zone)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: (f32, f32), expected: (f32, f32)) {
+        assert!(
+            (actual.0 - expected.0).abs() < 1e-6 && (actual.1 - expected.1).abs() < 1e-6,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn stick_inside_dead_zone_is_centered() {
+        assert_eq!(apply_stick_dead_zone(0.1, -0.1, 0.2), (0.0, 0.0));
+        assert_eq!(apply_stick_dead_zone(0.2, 0.0, 0.2), (0.0, 0.0));
+    }
+
+    #[test]
+    fn stick_rescales_past_dead_zone() {
+        assert_close(apply_stick_dead_zone(1.0, 0.0, 0.2), (1.0, 0.0));
+        assert_close(apply_stick_dead_zone(0.0, -0.6, 0.2), (0.0, -0.5));
+        // Only the magnitude is remapped, the direction stays.
+        assert_close(apply_stick_dead_zone(0.6, 0.8, 0.2), (0.6, 0.8));
+        assert_close(apply_stick_dead_zone(0.3, 0.4, 0.0), (0.3, 0.4));
+    }
+
+    #[test]
+    fn stick_magnitude_is_capped() {
+        let diagonal = std::f32::consts::FRAC_1_SQRT_2;
+        assert_close(apply_stick_dead_zone(1.0, 1.0, 0.2), (diagonal, diagonal));
+    }
+
+    #[test]
+    fn trigger_remapping() {
+        assert_eq!(apply_trigger_dead_zone(-1.0, 0.1), 0.0);
+        assert_eq!(apply_trigger_dead_zone(-0.85, 0.1), 0.0);
+        assert!((apply_trigger_dead_zone(0.0, 0.1) - 4.0 / 9.0).abs() < 1e-6);
+        assert_eq!(apply_trigger_dead_zone(1.0, 0.1), 1.0);
+        assert_eq!(apply_trigger_dead_zone(1.5, 0.1), 1.0);
+    }
+}
@@ -0,0 +1,4 @@
+pub mod actions;
+pub mod events;
+pub mod gamepad;
+pub mod state;
@@ -0,0 +1,334 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use super::events::{GamepadAxis, GamepadButton, InputEvent, Key, MouseButton};
+
+/// Keyboard, mouse and gamepad state built from the events of the current frame.
+///
+/// `is_*_down` reflects the held state, `was_*_pressed` / `was_*_released`
+/// are only true during the frame the transition happened in.
+#[derive(Default)]
+pub struct Input {
+    keys_down: HashSet<Key>,
+    keys_pressed: HashSet<Key>,
+    keys_released: HashSet<Key>,
+
+    buttons_down: HashSet<MouseButton>,
+    buttons_pressed: HashSet<MouseButton>,
+    buttons_released: HashSet<MouseButton>,
+
+    gamepad_buttons_down: HashSet<(usize, GamepadButton)>,
+    gamepad_buttons_pressed: HashSet<(usize, GamepadButton)>,
+    gamepad_buttons_released: HashSet<(usize, GamepadButton)>,
+    gamepad_axes: HashMap<(usize, GamepadAxis), f32>,
+    gamepads: BTreeMap<usize, String>,
+
+    mouse_position: (f64, f64),
+    mouse_delta: (f64, f64),
+    scroll_delta: (f64, f64),
+    cursor_inside: bool,
+    focused: bool,
+
+    text: String,
+    events: Vec<InputEvent>,
+}
+
+impl Input {
+    pub fn new() -> Input {
+        Input {
+            focused: true,
+            ..Default::default()
+        }
+    }
+
+    /// Drops everything that only lives for a single frame. Called by the
+    /// window before it feeds the newly polled events.
+    pub(crate) fn begin_frame(&mut self) {
+        self.keys_pressed.clear();
+        self.keys_released.clear();
+        self.buttons_pressed.clear();
+        self.buttons_released.clear();
+        self.gamepad_buttons_pressed.clear();
+        self.gamepad_buttons_released.clear();
+        self.mouse_delta = (0.0, 0.0);
+        self.scroll_delta = (0.0, 0.0);
+        self.text.clear();
+        self.events.clear();
+    }
+
+    pub(crate) fn handle_event(&mut self, event: InputEvent) {
+        match event {
+            InputEvent::KeyPressed { key, repeat, .. } => {
+                if !repeat && self.keys_down.insert(key) {
+                    self.keys_pressed.insert(key);
+                }
+            }
+            InputEvent::KeyReleased { key, .. } => {
+                if self.keys_down.remove(&key) {
+                    self.keys_released.insert(key);
+                }
+            }
+            InputEvent::Char(c) => self.text.push(c),
+            InputEvent::MouseButtonPressed { button, .. } => {
+                if self.buttons_down.insert(button) {
+                    self.buttons_pressed.insert(button);
+                }
+            }
+            InputEvent::MouseButtonReleased { button, .. } => {
+                if self.buttons_down.remove(&button) {
+                    self.buttons_released.insert(button);
+                }
+            }
+            InputEvent::CursorMoved { x, y } => {
+                if self.cursor_inside {
+                    self.mouse_delta.0 += x - self.mouse_position.0;
+                    self.mouse_delta.1 += y - self.mouse_position.1;
+                }
+                self.mouse_position = (x, y);
+                self.cursor_inside = true;
+            }
+            InputEvent::CursorEntered(entered) => self.cursor_inside = entered,
+            InputEvent::Scrolled { x, y } => {
+                self.scroll_delta.0 += x;
+                self.scroll_delta.1 += y;
+            }
+            InputEvent::GamepadConnected { gamepad, ref name } => {
+                self.gamepads.insert(gamepad, name.clone());
+            }
+            InputEvent::GamepadDisconnected { gamepad } => {
+                self.gamepads.remove(&gamepad);
+                self.gamepad_axes.retain(|&(g, _), _| g != gamepad);
+            }
+            InputEvent::GamepadButtonPressed { gamepad, button } => {
+                if self.gamepad_buttons_down.insert((gamepad, button)) {
+                    self.gamepad_buttons_pressed.insert((gamepad, button));
+                }
+            }
+            InputEvent::GamepadButtonReleased { gamepad, button } => {
+                if self.gamepad_buttons_down.remove(&(gamepad, button)) {
+                    self.gamepad_buttons_released.insert((gamepad, button));
+                }
+            }
+            InputEvent::GamepadAxisMoved {
+                gamepad,
+                axis,
+                value,
+            } => {
+                self.gamepad_axes.insert((gamepad, axis), value);
+            }
+            InputEvent::Focused(focused) => {
+                self.focused = focused;
+
+                // Releases happening while unfocused never reach us, so
+                // don't leave keys stuck down.
+                if !focused {
+                    self.keys_released.extend(self.keys_down.drain());
+                    self.buttons_released.extend(self.buttons_down.drain());
+                }
+            }
+        }
+
+        self.events.push(event);
+    }
+
+    pub fn is_key_down(&self, key: Key) -> bool {
+        self.keys_down.contains(&key)
+    }
+
+    pub fn was_key_pressed(&self, key: Key) -> bool {
+        self.keys_pressed.contains(&key)
+    }
+
+    pub fn was_key_released(&self, key: Key) -> bool {
+        self.keys_released.contains(&key)
+    }
+
+    pub fn is_mouse_button_down(&self, button: MouseButton) -> bool {
+        self.buttons_down.contains(&button)
+    }
+
+    pub fn was_mouse_button_pressed(&self, button: MouseButton) -> bool {
+        self.buttons_pressed.contains(&button)
+    }
+
+    pub fn was_mouse_button_released(&self, button: MouseButton) -> bool {
+        self.buttons_released.contains(&button)
+    }
+
+    /// True if `button` is held on any connected gamepad.
+    pub fn is_gamepad_button_down(&self, button: GamepadButton) -> bool {
+        self.gamepad_buttons_down.iter().any(|&(_, b)| b == button)
+    }
+
+    pub fn was_gamepad_button_pressed(&self, button: GamepadButton) -> bool {
+        self.gamepad_buttons_pressed
+            .iter()
+            .any(|&(_, b)| b == button)
+    }
+
+    pub fn was_gamepad_button_released(&self, button: GamepadButton) -> bool {
+        self.gamepad_buttons_released
+            .iter()
+            .any(|&(_, b)| b == button)
+    }
+
+    pub fn is_gamepad_button_down_for(&self, gamepad: usize, button: GamepadButton) -> bool {
+        self.gamepad_buttons_down.contains(&(gamepad, button))
+    }
+
+    /// Value of `axis` on the gamepad pushing it the furthest, sticks are in
+    /// `-1.0..=1.0` and triggers in `0.0..=1.0`.
+    pub fn gamepad_axis(&self, axis: GamepadAxis) -> f32 {
+        self.gamepad_axes
+            .iter()
+            .filter(|(&(_, a), _)| a == axis)
+            .map(|(_, &value)| value)
+            .fold(0.0, |best, value| match value.abs() > best.abs() {
+                true => value,
+                false => best,
+            })
+    }
+
+    pub fn gamepad_axis_for(&self, gamepad: usize, axis: GamepadAxis) -> f32 {
+        self.gamepad_axes
+            .get(&(gamepad, axis))
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    pub fn connected_gamepads(&self) -> impl Iterator<Item = (usize, &str)> {
+        self.gamepads.iter().map(|(&id, name)| (id, name.as_str()))
+    }
+
+    pub fn mouse_position(&self) -> (f64, f64) {
+        self.mouse_position
+    }
+
+    pub fn mouse_delta(&self) -> (f64, f64) {
+        self.mouse_delta
+    }
+
+    pub fn scroll_delta(&self) -> (f64, f64) {
+        self.scroll_delta
+    }
+
+    pub fn is_cursor_inside(&self) -> bool {
+        self.cursor_inside
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// Text typed during this frame, already translated by the keyboard layout.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn events(&self) -> &[InputEvent] {
+        &self.events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::events::Modifiers;
+
+    fn press(input: &mut Input, key: Key, repeat: bool) {
+        input.handle_event(InputEvent::KeyPressed {
+            key,
+            scancode: 0,
+            modifiers: Modifiers::default(),
+            repeat,
+        });
+    }
+
+    fn release(input: &mut Input, key: Key) {
+        input.handle_event(InputEvent::KeyReleased {
+            key,
+            scancode: 0,
+            modifiers: Modifiers::default(),
+        });
+    }
+
+    #[test]
+    fn key_transitions_last_one_frame() {
+        let mut input = Input::new();
+
+        press(&mut input, Key::A, false);
+        assert!(input.is_key_down(Key::A));
+        assert!(input.was_key_pressed(Key::A));
+        assert!(!input.was_key_released(Key::A));
+
+        input.begin_frame();
+        press(&mut input, Key::A, true);
+        assert!(input.is_key_down(Key::A));
+        assert!(!input.was_key_pressed(Key::A));
+
+        input.begin_frame();
+        release(&mut input, Key::A);
+        assert!(!input.is_key_down(Key::A));
+        assert!(input.was_key_released(Key::A));
+
+        input.begin_frame();
+        assert!(!input.was_key_released(Key::A));
+    }
+
+    #[test]
+    fn press_and_release_in_one_frame() {
+        let mut input = Input::new();
+
+        press(&mut input, Key::Space, false);
+        release(&mut input, Key::Space);
+
+        assert!(!input.is_key_down(Key::Space));
+        assert!(input.was_key_pressed(Key::Space));
+        assert!(input.was_key_released(Key::Space));
+    }
+
+    #[test]
+    fn release_without_press_is_ignored() {
+        let mut input = Input::new();
+        release(&mut input, Key::Escape);
+
+        assert!(!input.was_key_released(Key::Escape));
+    }
+
+    #[test]
+    fn losing_focus_releases_everything() {
+        let mut input = Input::new();
+        press(&mut input, Key::W, false);
+        input.handle_event(InputEvent::MouseButtonPressed {
+            button: MouseButton::Left,
+            modifiers: Modifiers::default(),
+        });
+
+        input.begin_frame();
+        input.handle_event(InputEvent::Focused(false));
+
+        assert!(!input.is_key_down(Key::W));
+        assert!(input.was_key_released(Key::W));
+        assert!(!input.is_mouse_button_down(MouseButton::Left));
+        assert!(input.was_mouse_button_released(MouseButton::Left));
+    }
+
+    #[test]
+    fn gamepad_button_transitions() {
+        let mut input = Input::new();
+        let event = |button| InputEvent::GamepadButtonPressed { gamepad: 1, button };
+
+        input.handle_event(event(GamepadButton::A));
+        assert!(input.was_gamepad_button_pressed(GamepadButton::A));
+        assert!(input.is_gamepad_button_down_for(1, GamepadButton::A));
+        assert!(!input.is_gamepad_button_down_for(0, GamepadButton::A));
+
+        input.begin_frame();
+        input.handle_event(InputEvent::GamepadButtonReleased {
+            gamepad: 1,
+            button: GamepadButton::A,
+        });
+        assert!(!input.was_gamepad_button_pressed(GamepadButton::A));
+        assert!(input.was_gamepad_button_released(GamepadButton::A));
+        assert!(!input.is_gamepad_button_down(GamepadButton::A));
+    }
+}
@@ -1,4 +1,15 @@
+// Lets `#[derive(Vertex)]` name `::ashen_engine` from inside the crate too.
+extern crate self as ashen_engine;
+
+pub mod app;
+pub mod cursor;
+#[cfg(target_os = "linux")]
+mod egl;
 pub mod errors;
 pub mod graphics;
+pub mod headless;
+pub mod input;
 pub mod logger;
+pub mod monitor;
+pub mod time;
 pub mod window;
\ No newline at end of file
@@ -0,0 +1,99 @@
+use glfw::Glfw;
+
+use crate::errors::WindowErrors;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VideoMode {
+    pub width: u32,
+    pub height: u32,
+    pub refresh_rate: u32,
+    pub red_bits: u32,
+    pub green_bits: u32,
+    pub blue_bits: u32,
+}
+
+impl VideoMode {
+    /// Same resolution and refresh rate, ignoring the color depth.
+    pub fn matches(&self, other: &VideoMode) -> bool {
+        (self.width, self.height, self.refresh_rate)
+            == (other.width, other.height, other.refresh_rate)
+    }
+}
+
+impl From<glfw::VidMode> for VideoMode {
+    fn from(mode: glfw::VidMode) -> VideoMode {
+        VideoMode {
+            width: mode.width,
+            height: mode.height,
+            refresh_rate: mode.refresh_rate,
+            red_bits: mode.red_bits,
+            green_bits: mode.green_bits,
+            blue_bits: mode.blue_bits,
+        }
+    }
+}
+
+/// Snapshot of a connected monitor. `index` is what the window API takes to
+/// target it, index 0 is always the primary monitor.
+#[derive(Clone, Debug)]
+pub struct Monitor {
+    pub index: usize,
+    pub name: String,
+    pub position: (i32, i32),
+    /// Physical size in millimetres, (0, 0) if the OS doesn't know it.
+    pub physical_size: (i32, i32),
+    pub content_scale: (f32, f32),
+    /// Area not covered by task bars or docks, as (x, y, width, height).
+    pub work_area: (i32, i32, i32, i32),
+    pub current_mode: Option<VideoMode>,
+    pub video_modes: Vec<VideoMode>,
+}
+
+impl Monitor {
+    fn from_glfw(index: usize, monitor: &glfw::Monitor) -> Monitor {
+        Monitor {
+            index,
+            name: monitor.get_name().unwrap_or_default(),
+            position: monitor.get_pos(),
+            physical_size: monitor.get_physical_size(),
+            content_scale: monitor.get_content_scale(),
+            work_area: monitor.get_workarea(),
+            current_mode: monitor.get_video_mode().map(VideoMode::from),
+            video_modes: monitor
+                .get_video_modes()
+                .into_iter()
+                .map(VideoMode::from)
+                .collect(),
+        }
+    }
+
+    pub fn is_primary(&self) -> bool {
+        self.index == 0
+    }
+}
+
+pub(crate) fn connected_monitors(glfw: &mut Glfw) -> Vec<Monitor> {
+    glfw.with_connected_monitors(|_, monitors| {
+        monitors
+            .iter()
+            .enumerate()
+            .map(|(index, monitor)| Monitor::from_glfw(index, monitor))
+            .collect()
+    })
+}
+
+/// Checks `video_mode` is one the monitor can actually switch to.
+pub(crate) fn validate_video_mode(
+    monitor: &glfw::Monitor,
+    video_mode: &VideoMode,
+) -> Result<(), WindowErrors> {
+    let supported = monitor
+        .get_video_modes()
+        .into_iter()
+        .any(|mode| VideoMode::from(mode).matches(video_mode));
+
+    match supported {
+        true => Ok(()),
+        false => Err(WindowErrors::UnsupportedVideoMode(*video_mode)),
+    }
+}
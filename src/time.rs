@@ -0,0 +1,231 @@
+use std::{collections::VecDeque, thread, time::Duration};
+
+const DEFAULT_HISTORY: usize = 240;
+const FPS_SMOOTHING: f64 = 0.1;
+
+/// Frame timing owned by the window, ticked once per frame.
+///
+/// Frame time statistics (`min`, `max`, percentiles) cover the last
+/// `history` frames.
+pub struct Time {
+    last_tick: Option<f64>,
+    delta: f64,
+    total: f64,
+    frame: u64,
+    smoothed_frame_time: f64,
+    frame_times: VecDeque<f64>,
+    history: usize,
+    frame_rate_limit: Option<f64>,
+}
+
+impl Default for Time {
+    fn default() -> Self {
+        Time::new()
+    }
+}
+
+impl Time {
+    pub fn new() -> Time {
+        Time {
+            last_tick: None,
+            delta: 0.0,
+            total: 0.0,
+            frame: 0,
+            smoothed_frame_time: 0.0,
+            frame_times: VecDeque::with_capacity(DEFAULT_HISTORY),
+            history: DEFAULT_HISTORY,
+            frame_rate_limit: None,
+        }
+    }
+
+    /// Starts a new frame at `now` seconds.
+    pub(crate) fn tick(&mut self, now: f64) {
+        let last_tick = self.last_tick.replace(now);
+        let Some(last_tick) = last_tick else {
+            return;
+        };
+
+        self.delta = (now - last_tick).max(0.0);
+        self.total += self.delta;
+        self.frame += 1;
+
+        self.smoothed_frame_time = match self.frame {
+            1 => self.delta,
+            _ => self.smoothed_frame_time + (self.delta - self.smoothed_frame_time) * FPS_SMOOTHING,
+        };
+
+        if self.frame_times.len() == self.history {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(self.delta);
+    }
+
+    /// Blocks until the frame started at the last tick has lasted as long as
+    /// the frame rate limit asks for. `now` is the current time in seconds.
+    pub(crate) fn wait_for_frame_limit(&self, now: f64) {
+        let (Some(limit), Some(last_tick)) = (self.frame_rate_limit, self.last_tick) else {
+            return;
+        };
+
+        let remaining = 1.0 / limit - (now - last_tick);
+        if remaining > 0.0 {
+            thread::sleep(Duration::from_secs_f64(remaining));
+        }
+    }
+
+    /// Seconds between the two last frames.
+    pub fn delta(&self) -> f64 {
+        self.delta
+    }
+
+    /// Seconds accumulated since the first frame.
+    pub fn total(&self) -> f64 {
+        self.total
+    }
+
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Frames per second from an exponentially smoothed frame time, stable
+    /// enough to show on screen.
+    pub fn fps(&self) -> f64 {
+        match self.smoothed_frame_time > 0.0 {
+            true => 1.0 / self.smoothed_frame_time,
+            false => 0.0,
+        }
+    }
+
+    pub fn min_frame_time(&self) -> f64 {
+        match self.frame_times.is_empty() {
+            true => 0.0,
+            false => self
+                .frame_times
+                .iter()
+                .copied()
+                .fold(f64::INFINITY, f64::min),
+        }
+    }
+
+    pub fn max_frame_time(&self) -> f64 {
+        self.frame_times.iter().copied().fold(0.0, f64::max)
+    }
+
+    pub fn average_frame_time(&self) -> f64 {
+        match self.frame_times.is_empty() {
+            true => 0.0,
+            false => self.frame_times.iter().sum::<f64>() / self.frame_times.len() as f64,
+        }
+    }
+
+    /// Frame time below which `percentile`% of the recorded frames fall,
+    /// e.g. `frame_time_percentile(99.0)` for the 1% lows.
+    pub fn frame_time_percentile(&self, percentile: f64) -> f64 {
+        if self.frame_times.is_empty() {
+            return 0.0;
+        }
+
+        let mut sorted: Vec<f64> = self.frame_times.iter().copied().collect();
+        sorted.sort_by(f64::total_cmp);
+
+        let rank = (percentile.clamp(0.0, 100.0) / 100.0 * sorted.len() as f64).ceil() as usize;
+        sorted[rank.saturating_sub(1)]
+    }
+
+    /// Number of frames the statistics are computed over.
+    pub fn set_history(&mut self, frames: usize) {
+        self.history = frames.max(1);
+
+        while self.frame_times.len() > self.history {
+            self.frame_times.pop_front();
+        }
+    }
+
+    /// Caps the frame rate by sleeping after presenting, `None` to uncap.
+    pub fn set_frame_rate_limit(&mut self, fps: Option<f64>) {
+        self.frame_rate_limit = fps.filter(|fps| *fps > 0.0);
+    }
+
+    pub fn frame_rate_limit(&self) -> Option<f64> {
+        self.frame_rate_limit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A `Time` ticked once per given frame time, after a first tick at 0.
+    fn time_with_frames(frame_times: &[f64]) -> Time {
+        let mut time = Time::new();
+        let mut now = 0.0;
+        time.tick(now);
+        for frame_time in frame_times {
+            now += frame_time;
+            time.tick(now);
+        }
+
+        time
+    }
+
+    #[test]
+    fn first_tick_starts_timing() {
+        let mut time = Time::new();
+        time.tick(10.0);
+
+        assert_eq!(time.frame(), 0);
+        assert_eq!(time.delta(), 0.0);
+        assert_eq!(time.max_frame_time(), 0.0);
+        assert_eq!(time.frame_time_percentile(50.0), 0.0);
+    }
+
+    #[test]
+    fn frame_time_statistics() {
+        let time = time_with_frames(&[0.25, 0.5, 0.125, 0.125]);
+
+        assert_eq!(time.frame(), 4);
+        assert_eq!(time.delta(), 0.125);
+        assert_eq!(time.total(), 1.0);
+        assert_eq!(time.min_frame_time(), 0.125);
+        assert_eq!(time.max_frame_time(), 0.5);
+        assert_eq!(time.average_frame_time(), 0.25);
+    }
+
+    #[test]
+    fn clock_going_backwards_is_a_zero_delta() {
+        let mut time = time_with_frames(&[0.5]);
+        time.tick(0.25);
+
+        assert_eq!(time.delta(), 0.0);
+        assert_eq!(time.total(), 0.5);
+    }
+
+    #[test]
+    fn percentiles() {
+        let time = time_with_frames(&[7.0, 3.0, 10.0, 1.0, 5.0, 2.0, 9.0, 4.0, 8.0, 6.0]);
+
+        assert_eq!(time.frame_time_percentile(0.0), 1.0);
+        assert_eq!(time.frame_time_percentile(10.0), 1.0);
+        assert_eq!(time.frame_time_percentile(50.0), 5.0);
+        assert_eq!(time.frame_time_percentile(55.0), 6.0);
+        assert_eq!(time.frame_time_percentile(99.0), 10.0);
+        assert_eq!(time.frame_time_percentile(100.0), 10.0);
+        assert_eq!(time.frame_time_percentile(250.0), 10.0);
+    }
+
+    #[test]
+    fn history_trimming() {
+        let mut time = time_with_frames(&[4.0, 1.0, 2.0, 3.0]);
+
+        time.set_history(2);
+        assert_eq!(time.min_frame_time(), 2.0);
+        assert_eq!(time.max_frame_time(), 3.0);
+
+        time.tick(time.total() + 5.0);
+        assert_eq!(time.min_frame_time(), 3.0);
+        assert_eq!(time.max_frame_time(), 5.0);
+
+        time.set_history(0);
+        assert_eq!(time.average_frame_time(), 5.0);
+    }
+}
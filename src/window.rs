@@ -1,75 +1,758 @@
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::Receiver;
+use std::thread::JoinHandle;
 
-use glfw::{Context, Glfw, Key};
+use glfw::{Context, Glfw};
+use image::RgbaImage;
+
+use crate::cursor::{Cursor, CursorMode};
+use crate::errors::{Errors, WindowErrors};
+use crate::graphics::context::GlContext;
+use crate::graphics::screenshot;
+use crate::input::events::InputEvent;
+use crate::input::gamepad::Gamepads;
+use crate::input::state::Input;
+use crate::logger;
+use crate::monitor::{self, validate_video_mode, Monitor, VideoMode};
+use crate::time::Time;
 
 pub struct Window {
     pub glfw: glfw::Glfw,
     window_handle: glfw::Window,
     events: Receiver<(f64, glfw::WindowEvent)>,
+    mode: WindowMode,
+    monitor: usize,
+    video_mode: Option<VideoMode>,
+    windowed_pos: (i32, i32),
+    windowed_size: (u32, u32),
+    context_version: Option<(u32, u32)>,
+    vsync: bool,
+    cursor_mode: CursorMode,
+    input: Input,
+    gamepads: Gamepads,
+    time: Time,
+    callbacks: Vec<(CallbackId, WindowCallback)>,
+    next_callback_id: CallbackId,
+    gl_context: Option<GlContext>,
+}
+
+pub type CallbackId = usize;
+
+enum WindowCallback {
+    Resize(Box<dyn FnMut(i32, i32)>),
+    ContentScale(Box<dyn FnMut(f32, f32)>),
+    Focus(Box<dyn FnMut(bool)>),
+    Iconify(Box<dyn FnMut(bool)>),
+    CloseRequested(Box<dyn FnMut() -> bool>),
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum WindowMode {
     Windowed,
     Fullscreen,
     Borderless,
 }
 
-impl Window {
-    pub fn new(width: u32, height: u32, title: &str, mode: WindowMode) -> Window {
-        let mut glfw: Glfw = glfw::init(glfw::FAIL_ON_ERRORS).unwrap();
-        let (mut window, events) = glfw
-            .with_primary_monitor(|glfw, monitors| match mode {
-                WindowMode::Windowed => {
-                    glfw.create_window(width, height, title, glfw::WindowMode::Windowed)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GlProfile {
+    Any,
+    Core,
+    Compat,
+}
+
+/// Which API creates the OpenGL context. `Egl` and `OsMesa` let us get a
+/// context on machines without a display server, `OsMesa` being pure software.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContextApi {
+    Native,
+    Egl,
+    OsMesa,
+}
+
+pub struct WindowBuilder {
+    width: u32,
+    height: u32,
+    title: String,
+    mode: WindowMode,
+    monitor: usize,
+    video_mode: Option<VideoMode>,
+    context_version: Option<(u32, u32)>,
+    profile: GlProfile,
+    samples: Option<u32>,
+    srgb: bool,
+    debug: bool,
+    vsync: bool,
+    resizable: bool,
+    visible: bool,
+    context_api: ContextApi,
+}
+
+impl WindowBuilder {
+    pub fn new(width: u32, height: u32, title: &str) -> WindowBuilder {
+        WindowBuilder {
+            width,
+            height,
+            title: title.to_owned(),
+            mode: WindowMode::Windowed,
+            monitor: 0,
+            video_mode: None,
+            context_version: None,
+            profile: GlProfile::Any,
+            samples: None,
+            srgb: false,
+            debug: false,
+            vsync: true,
+            resizable: true,
+            visible: true,
+            context_api: ContextApi::Native,
+        }
+    }
+
+    pub fn mode(mut self, mode: WindowMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Index of the monitor to open on, as listed by `Window::monitors`.
+    /// Defaults to the primary monitor.
+    pub fn monitor(mut self, monitor: usize) -> Self {
+        self.monitor = monitor;
+        self
+    }
+
+    /// Resolution and refresh rate to switch to in fullscreen mode. Without
+    /// one fullscreen keeps the monitor's current video mode.
+    pub fn video_mode(mut self, video_mode: VideoMode) -> Self {
+        self.video_mode = Some(video_mode);
+        self
+    }
+
+    pub fn context_version(mut self, major: u32, minor: u32) -> Self {
+        self.context_version = Some((major, minor));
+        self
+    }
+
+    pub fn profile(mut self, profile: GlProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Number of MSAA samples for the default framebuffer, `0` disables it.
+    pub fn samples(mut self, samples: u32) -> Self {
+        self.samples = Some(samples);
+        self
+    }
+
+    pub fn srgb(mut self, srgb: bool) -> Self {
+        self.srgb = srgb;
+        self
+    }
+
+    pub fn debug(mut self, debug: bool) -> Self {
+        self.debug = debug;
+        self
+    }
+
+    pub fn vsync(mut self, vsync: bool) -> Self {
+        self.vsync = vsync;
+        self
+    }
+
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    /// A hidden window still owns a working context, which is all we need to
+    /// render offscreen.
+    pub fn visible(mut self, visible: bool) -> Self {
+        self.visible = visible;
+        self
+    }
+
+    pub fn context_api(mut self, context_api: ContextApi) -> Self {
+        self.context_api = context_api;
+        self
+    }
+
+    pub fn build(self) -> Result<Window, Errors> {
+        let mut glfw: Glfw = glfw::init(Some(glfw::Callback {
+            f: log_glfw_error as fn(glfw::Error, String, &()),
+            data: (),
+        }))
+        .map_err(WindowErrors::InitFailed)?;
+
+        self.apply_hints(&mut glfw);
+
+        let WindowBuilder {
+            width,
+            height,
+            ref title,
+            mode,
+            monitor,
+            video_mode,
+            ..
+        } = self;
+
+        let created =
+            glfw.with_connected_monitors(|glfw, monitors| match (mode, monitors.get(monitor)) {
+                (WindowMode::Windowed, None) if monitor != 0 => {
+                    Err(WindowErrors::MonitorNotFound(monitor))
+                }
+                (WindowMode::Windowed, m) => {
+                    let (mut window, events) = glfw
+                        .create_window(width, height, title, glfw::WindowMode::Windowed)
+                        .ok_or(WindowErrors::CreationFailed)?;
+
+                    if let Some(m) = m.filter(|_| monitor != 0) {
+                        let (x, y) = centered_pos(m, (width, height));
+                        window.set_pos(x, y);
+                    }
+
+                    Ok((window, events))
+                }
+                (WindowMode::Fullscreen, Some(m)) => {
+                    let (w, h, refresh_rate) = match video_mode {
+                        Some(video_mode) => {
+                            validate_video_mode(m, &video_mode)?;
+                            (
+                                video_mode.width,
+                                video_mode.height,
+                                Some(video_mode.refresh_rate),
+                            )
+                        }
+                        None => match m.get_video_mode() {
+                            Some(current) => {
+                                (current.width, current.height, Some(current.refresh_rate))
+                            }
+                            None => (width, height, None),
+                        },
+                    };
+                    glfw.window_hint(glfw::WindowHint::RefreshRate(refresh_rate));
+
+                    glfw.create_window(w, h, title, glfw::WindowMode::FullScreen(m))
+                        .ok_or(WindowErrors::CreationFailed)
+                }
+                (WindowMode::Borderless, Some(m)) => {
+                    let (x, y, w, h) = borderless_rect(m, (width, height));
+
+                    glfw.window_hint(glfw::WindowHint::Decorated(false));
+                    let (mut window, events) = glfw
+                        .create_window(w, h, title, glfw::WindowMode::Windowed)
+                        .ok_or(WindowErrors::CreationFailed)?;
+                    window.set_pos(x, y);
+
+                    Ok((window, events))
                 }
-                WindowMode::Fullscreen => glfw.create_window(
-                    width,
-                    height,
-                    title,
-                    monitors.map_or(glfw::WindowMode::Windowed, |m| {
-                        glfw::WindowMode::FullScreen(m)
-                    }),
-                ),
-                WindowMode::Borderless => todo!(),
-            })
-            .expect("Failed to create glfw window");
+                (_, None) if monitors.is_empty() => Err(WindowErrors::UnsupportedMode(mode)),
+                (_, None) => Err(WindowErrors::MonitorNotFound(monitor)),
+            });
+        glfw.default_window_hints();
+
+        let (mut window, events) = created?;
 
         window.set_framebuffer_size_polling(true);
         window.set_key_polling(true);
+        window.set_char_polling(true);
+        window.set_mouse_button_polling(true);
+        window.set_cursor_pos_polling(true);
+        window.set_cursor_enter_polling(true);
+        window.set_scroll_polling(true);
+        window.set_focus_polling(true);
+        window.set_iconify_polling(true);
+        window.set_content_scale_polling(true);
+        window.set_close_polling(true);
 
-        Window {
+        let windowed_pos = match mode {
+            WindowMode::Windowed => window.get_pos(),
+            _ => (0, 0),
+        };
+
+        let gamepads = Gamepads::new(glfw.clone());
+
+        Ok(Window {
             glfw,
             window_handle: window,
             events,
+            mode,
+            monitor,
+            video_mode,
+            windowed_pos,
+            windowed_size: (width, height),
+            context_version: self.context_version,
+            vsync: self.vsync,
+            cursor_mode: CursorMode::Normal,
+            input: Input::new(),
+            gamepads,
+            time: Time::new(),
+            callbacks: Vec::new(),
+            next_callback_id: 0,
+            gl_context: None,
+        })
+    }
+
+    fn apply_hints(&self, glfw: &mut Glfw) {
+        if let Some((major, minor)) = self.context_version {
+            glfw.window_hint(glfw::WindowHint::ContextVersion(major, minor));
+        }
+
+        match self.profile {
+            GlProfile::Any => {}
+            GlProfile::Core => {
+                glfw.window_hint(glfw::WindowHint::OpenGlProfile(
+                    glfw::OpenGlProfileHint::Core,
+                ));
+                if cfg!(target_os = "macos") {
+                    glfw.window_hint(glfw::WindowHint::OpenGlForwardCompat(true));
+                }
+            }
+            GlProfile::Compat => glfw.window_hint(glfw::WindowHint::OpenGlProfile(
+                glfw::OpenGlProfileHint::Compat,
+            )),
         }
+
+        glfw.window_hint(glfw::WindowHint::Samples(self.samples));
+        glfw.window_hint(glfw::WindowHint::SRgbCapable(self.srgb));
+        glfw.window_hint(glfw::WindowHint::OpenGlDebugContext(self.debug));
+        glfw.window_hint(glfw::WindowHint::Resizable(self.resizable));
+        glfw.window_hint(glfw::WindowHint::Visible(self.visible));
+        glfw.window_hint(glfw::WindowHint::ContextCreationApi(
+            match self.context_api {
+                ContextApi::Native => glfw::ContextCreationApi::Native,
+                ContextApi::Egl => glfw::ContextCreationApi::Egl,
+                ContextApi::OsMesa => glfw::ContextCreationApi::OsMesa,
+            },
+        ));
+    }
+}
+
+impl Window {
+    pub fn new(width: u32, height: u32, title: &str, mode: WindowMode) -> Result<Window, Errors> {
+        Window::builder(width, height, title).mode(mode).build()
+    }
+
+    pub fn builder(width: u32, height: u32, title: &str) -> WindowBuilder {
+        WindowBuilder::new(width, height, title)
     }
 
-    pub fn init_gl(&mut self) {
+    /// Makes the context current, loads the GL functions and checks the
+    /// driver gave us at least the context version that was requested. The
+    /// returned handle is what GL objects are created from.
+    pub fn init_gl(&mut self) -> Result<GlContext, Errors> {
         self.window_handle.make_current();
         gl::load_with(|s| self.window_handle.get_proc_address(s) as *const _);
+
+        self.glfw.set_swap_interval(match self.vsync {
+            true => glfw::SwapInterval::Sync(1),
+            false => glfw::SwapInterval::None,
+        });
+
+        if let Some((major, minor)) = self.context_version {
+            let version = self.window_handle.get_context_version();
+
+            if (version.major, version.minor) < (major as u64, minor as u64) {
+                return Err(WindowErrors::ContextVersionMismatch {
+                    requested: (major, minor),
+                    actual: (version.major as u32, version.minor as u32),
+                }
+                .into());
+            }
+        }
+
+        let window = self.window_handle.window_ptr();
+        let context = self.gl_context.get_or_insert_with(|| {
+            GlContext::new(move || unsafe { glfw::ffi::glfwGetCurrentContext() } == window)
+        });
+        Ok(context.clone())
+    }
+
+    /// Makes this window's context current again, after rendering with
+    /// another window or a headless context on this thread.
+    pub fn make_current(&mut self) {
+        self.window_handle.make_current();
+    }
+
+    pub fn mode(&self) -> WindowMode {
+        self.mode
+    }
+
+    /// Index of the monitor the window is placed on.
+    pub fn monitor(&self) -> usize {
+        self.monitor
+    }
+
+    pub fn monitors(&mut self) -> Vec<Monitor> {
+        monitor::connected_monitors(&mut self.glfw)
+    }
+
+    /// Switches between windowed, fullscreen and borderless on the current
+    /// monitor. The GL context is kept, only the window is reconfigured.
+    pub fn set_mode(&mut self, mode: WindowMode) -> Result<(), Errors> {
+        if mode == self.mode {
+            return Ok(());
+        }
+
+        self.apply_mode(mode, self.monitor, self.video_mode)
+    }
+
+    /// Goes fullscreen on `monitor`, switching to `video_mode` if given or
+    /// keeping the desktop resolution otherwise.
+    pub fn set_fullscreen(
+        &mut self,
+        monitor: usize,
+        video_mode: Option<VideoMode>,
+    ) -> Result<(), Errors> {
+        self.apply_mode(WindowMode::Fullscreen, monitor, video_mode)
+    }
+
+    /// Moves the window to `monitor`, keeping its current mode.
+    pub fn move_to_monitor(&mut self, monitor: usize) -> Result<(), Errors> {
+        let video_mode = match monitor == self.monitor {
+            true => self.video_mode,
+            false => None,
+        };
+
+        self.apply_mode(self.mode, monitor, video_mode)
+    }
+
+    fn apply_mode(
+        &mut self,
+        mode: WindowMode,
+        monitor: usize,
+        video_mode: Option<VideoMode>,
+    ) -> Result<(), Errors> {
+        if self.mode == WindowMode::Windowed {
+            let (width, height) = self.window_handle.get_size();
+            self.windowed_pos = self.window_handle.get_pos();
+            self.windowed_size = (width as u32, height as u32);
+        }
+
+        let window = &mut self.window_handle;
+        let (pos, size) = (self.windowed_pos, self.windowed_size);
+        let same_monitor = monitor == self.monitor;
+
+        let windowed_pos = self.glfw.with_connected_monitors(|_, monitors| {
+            match (mode, monitors.get(monitor)) {
+                (WindowMode::Windowed, None) if monitor != 0 => {
+                    Err(WindowErrors::MonitorNotFound(monitor))
+                }
+                (WindowMode::Windowed, m) => {
+                    let pos = match m.filter(|_| !same_monitor) {
+                        Some(m) => centered_pos(m, size),
+                        None => pos,
+                    };
+
+                    window.set_monitor(
+                        glfw::WindowMode::Windowed,
+                        pos.0,
+                        pos.1,
+                        size.0,
+                        size.1,
+                        None,
+                    );
+                    window.set_decorated(true);
+                    Ok(pos)
+                }
+                (WindowMode::Fullscreen, Some(m)) => {
+                    let (width, height, refresh_rate) = match video_mode {
+                        Some(video_mode) => {
+                            validate_video_mode(m, &video_mode)?;
+                            (
+                                video_mode.width,
+                                video_mode.height,
+                                Some(video_mode.refresh_rate),
+                            )
+                        }
+                        None => match m.get_video_mode() {
+                            Some(current) => {
+                                (current.width, current.height, Some(current.refresh_rate))
+                            }
+                            None => (size.0, size.1, None),
+                        },
+                    };
+
+                    window.set_decorated(true);
+                    window.set_monitor(
+                        glfw::WindowMode::FullScreen(m),
+                        0,
+                        0,
+                        width,
+                        height,
+                        refresh_rate,
+                    );
+                    Ok(pos)
+                }
+                (WindowMode::Borderless, Some(m)) => {
+                    let (x, y, w, h) = borderless_rect(m, size);
+
+                    window.set_monitor(glfw::WindowMode::Windowed, x, y, w, h, None);
+                    window.set_decorated(false);
+                    Ok(pos)
+                }
+                (_, None) if monitors.is_empty() => Err(WindowErrors::UnsupportedMode(mode)),
+                (_, None) => Err(WindowErrors::MonitorNotFound(monitor)),
+            }
+        })?;
+
+        self.mode = mode;
+        self.monitor = monitor;
+        self.video_mode = video_mode;
+        self.windowed_pos = windowed_pos;
+        Ok(())
     }
 
     pub fn should_close(&self) -> bool {
         self.window_handle.should_close()
     }
 
-    pub fn update(&mut self) {
-        self.process_events();
+    pub fn set_should_close(&mut self, value: bool) {
+        self.window_handle.set_should_close(value)
+    }
+
+    pub fn framebuffer_size(&self) -> (i32, i32) {
+        self.window_handle.get_framebuffer_size()
+    }
+
+    pub fn content_scale(&self) -> (f32, f32) {
+        self.window_handle.get_content_scale()
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.window_handle.is_focused()
+    }
+
+    pub fn is_iconified(&self) -> bool {
+        self.window_handle.is_iconified()
+    }
+
+    /// Called with the new framebuffer size in pixels, after the viewport
+    /// has been updated.
+    pub fn on_resize(&mut self, callback: impl FnMut(i32, i32) + 'static) -> CallbackId {
+        self.add_callback(WindowCallback::Resize(Box::new(callback)))
+    }
+
+    /// Called when the window moves to a monitor with a different DPI scale.
+    pub fn on_content_scale_change(
+        &mut self,
+        callback: impl FnMut(f32, f32) + 'static,
+    ) -> CallbackId {
+        self.add_callback(WindowCallback::ContentScale(Box::new(callback)))
+    }
+
+    pub fn on_focus_change(&mut self, callback: impl FnMut(bool) + 'static) -> CallbackId {
+        self.add_callback(WindowCallback::Focus(Box::new(callback)))
+    }
+
+    /// Called with `true` when the window gets minimized, `false` when restored.
+    pub fn on_iconify(&mut self, callback: impl FnMut(bool) + 'static) -> CallbackId {
+        self.add_callback(WindowCallback::Iconify(Box::new(callback)))
+    }
+
+    /// Called when the user tries to close the window. Returning `false`
+    /// from any subscriber vetoes the close.
+    pub fn on_close_requested(&mut self, callback: impl FnMut() -> bool + 'static) -> CallbackId {
+        self.add_callback(WindowCallback::CloseRequested(Box::new(callback)))
+    }
+
+    pub fn remove_callback(&mut self, id: CallbackId) {
+        self.callbacks.retain(|(callback_id, _)| *callback_id != id);
+    }
+
+    fn add_callback(&mut self, callback: WindowCallback) -> CallbackId {
+        let id = self.next_callback_id;
+        self.next_callback_id += 1;
+        self.callbacks.push((id, callback));
+        id
+    }
+
+    pub fn cursor_mode(&self) -> CursorMode {
+        self.cursor_mode
+    }
+
+    pub fn set_cursor_mode(&mut self, mode: CursorMode) {
+        let raw_motion = mode == CursorMode::Captured && self.glfw.supports_raw_motion();
+
+        self.window_handle.set_cursor_mode(match mode {
+            CursorMode::Normal => glfw::CursorMode::Normal,
+            CursorMode::Hidden => glfw::CursorMode::Hidden,
+            CursorMode::Captured => glfw::CursorMode::Disabled,
+        });
+        self.window_handle.set_raw_mouse_motion(raw_motion);
+        self.cursor_mode = mode;
+    }
+
+    /// Replaces the cursor shown over the window, `None` restores the default
+    /// arrow. Returns the previous cursor so it can be reused.
+    pub fn set_cursor(&mut self, cursor: Option<Cursor>) -> Option<Cursor> {
+        self.window_handle
+            .set_cursor(cursor.map(Cursor::into_handle))
+            .map(Cursor::from_handle)
+    }
+
+    pub fn clipboard_string(&self) -> Option<String> {
+        self.window_handle.get_clipboard_string()
+    }
+
+    pub fn set_clipboard_string(&mut self, value: &str) {
+        self.window_handle.set_clipboard_string(value)
+    }
+
+    /// Captures the back buffer, so call it after rendering and before
+    /// `present`, e.g. at the end of `App::render`.
+    pub fn screenshot(&self) -> RgbaImage {
+        let (width, height) = self.framebuffer_size();
+
+        screenshot::capture(0, width, height)
+    }
+
+    pub fn save_screenshot(&self, path: impl AsRef<Path>) -> Result<(), Errors> {
+        screenshot::save(&self.screenshot(), path)
+    }
+
+    /// Only the readback happens on this thread, encoding and writing the
+    /// file is left to a background one.
+    pub fn save_screenshot_in_background(
+        &self,
+        path: impl Into<PathBuf>,
+    ) -> JoinHandle<Result<(), Errors>> {
+        screenshot::save_in_background(self.screenshot(), path)
+    }
+
+    pub fn input(&self) -> &Input {
+        &self.input
+    }
+
+    pub fn time(&self) -> &Time {
+        &self.time
+    }
+
+    pub fn time_mut(&mut self) -> &mut Time {
+        &mut self.time
+    }
+
+    pub fn gamepads_mut(&mut self) -> &mut Gamepads {
+        &mut self.gamepads
+    }
+
+    /// Starts a new input frame and polls the pending window events.
+    pub fn poll_events(&mut self) {
+        self.input.begin_frame();
+        self.pump_events();
+    }
+
+    /// Polls events without resetting the per-frame input state, so pressed
+    /// and released transitions accumulate until `consume_input` is called.
+    pub(crate) fn pump_events(&mut self) {
+        self.time.tick(self.glfw.get_time());
         self.glfw.poll_events();
+        self.process_events();
+    }
+
+    pub(crate) fn consume_input(&mut self) {
+        self.input.begin_frame();
+    }
+
+    /// Swaps the buffers, then sleeps if a frame rate limit is set.
+    pub fn present(&mut self) {
         self.window_handle.swap_buffers();
+        self.time.wait_for_frame_limit(self.glfw.get_time());
     }
 
     fn process_events(&mut self) {
         for (_, event) in glfw::flush_messages(&self.events) {
+            let callbacks = self.callbacks.iter_mut().map(|(_, callback)| callback);
+
             match event {
-                glfw::WindowEvent::FramebufferSize(width, height) => unsafe {
-                    gl::Viewport(0, 0, width, height)
-                },
-                glfw::WindowEvent::Key(Key::Escape, _, glfw::Action::Press, _) => {
-                    self.window_handle.set_should_close(true)
+                glfw::WindowEvent::FramebufferSize(width, height) => {
+                    unsafe { gl::Viewport(0, 0, width, height) }
+
+                    for callback in callbacks {
+                        if let WindowCallback::Resize(f) = callback {
+                            f(width, height);
+                        }
+                    }
+                }
+                glfw::WindowEvent::ContentScale(x, y) => {
+                    for callback in callbacks {
+                        if let WindowCallback::ContentScale(f) = callback {
+                            f(x, y);
+                        }
+                    }
+                }
+                glfw::WindowEvent::Focus(focused) => {
+                    for callback in callbacks {
+                        if let WindowCallback::Focus(f) = callback {
+                            f(focused);
+                        }
+                    }
+                }
+                glfw::WindowEvent::Iconify(iconified) => {
+                    for callback in callbacks {
+                        if let WindowCallback::Iconify(f) = callback {
+                            f(iconified);
+                        }
+                    }
+                }
+                glfw::WindowEvent::Close => {
+                    // Every subscriber gets asked, even after one vetoed.
+                    let mut allow_close = true;
+                    for callback in callbacks {
+                        if let WindowCallback::CloseRequested(f) = callback {
+                            allow_close &= f();
+                        }
+                    }
+
+                    if !allow_close {
+                        self.window_handle.set_should_close(false);
+                    }
                 }
                 _ => {}
             }
+
+            if let Some(input_event) = InputEvent::from_glfw(&event) {
+                self.input.handle_event(input_event);
+            }
+        }
+
+        let mut gamepad_events = Vec::new();
+        self.gamepads.poll(&mut gamepad_events);
+
+        for event in gamepad_events {
+            self.input.handle_event(event);
         }
     }
 }
+
+impl Drop for Window {
+    fn drop(&mut self) {
+        // GL objects still around must not touch the context once it's gone.
+        if let Some(context) = &self.gl_context {
+            context.invalidate();
+        }
+    }
+}
+
+fn log_glfw_error(error: glfw::Error, description: String, _: &()) {
+    logger::error!("glfw error {}: {}", error, description);
+}
+
+/// Position centering a window of `size` in the monitor's work area.
+fn centered_pos(monitor: &glfw::Monitor, size: (u32, u32)) -> (i32, i32) {
+    let (x, y, width, height) = monitor.get_workarea();
+
+    (
+        x + (width - size.0 as i32) / 2,
+        y + (height - size.1 as i32) / 2,
+    )
+}
+
+/// Position and size covering the whole monitor at its current video mode,
+/// falling back to `fallback` if the mode can't be queried.
+fn borderless_rect(monitor: &glfw::Monitor, fallback: (u32, u32)) -> (i32, i32, u32, u32) {
+    let (x, y) = monitor.get_pos();
+
+    match monitor.get_video_mode() {
+        Some(video_mode) => (x, y, video_mode.width, video_mode.height),
+        None => (x, y, fallback.0, fallback.1),
+    }
+}
@@ -0,0 +1,111 @@
+use image::{Rgba, RgbaImage};
+
+/// Largest possible YIQ delta, between black and white.
+const MAX_YIQ_DELTA: f32 = 35215.0;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Tolerance {
+    /// Per channel difference that is always accepted, absorbs rounding
+    /// differences between drivers.
+    pub channel: u8,
+    /// Perceptual threshold in `0.0..1.0`, a pixel above `channel` still
+    /// passes if the YIQ color distance stays under it.
+    pub perceptual: f32,
+    /// Fraction of pixels allowed to fail before the whole image does.
+    pub max_failing: f32,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Tolerance {
+            channel: 2,
+            perceptual: 0.1,
+            max_failing: 0.001,
+        }
+    }
+}
+
+pub struct Comparison {
+    pub failing_pixels: u32,
+    pub total_pixels: u32,
+    pub max_channel_diff: u8,
+    /// Faded reference with failing pixels in red.
+    pub diff: RgbaImage,
+}
+
+impl Comparison {
+    pub fn passed(&self, tolerance: &Tolerance) -> bool {
+        self.failing_pixels as f32 <= self.total_pixels as f32 * tolerance.max_failing
+    }
+}
+
+pub fn compare(actual: &RgbaImage, expected: &RgbaImage, tolerance: &Tolerance) -> Comparison {
+    assert_eq!(
+        actual.dimensions(),
+        expected.dimensions(),
+        "image sizes differ"
+    );
+
+    let threshold = MAX_YIQ_DELTA * tolerance.perceptual * tolerance.perceptual;
+    let mut diff = RgbaImage::new(actual.width(), actual.height());
+    let mut failing_pixels = 0;
+    let mut max_channel_diff = 0;
+
+    for (x, y, a) in actual.enumerate_pixels() {
+        let e = expected.get_pixel(x, y);
+        let channel_diff =
+            a.0.iter()
+                .zip(e.0)
+                .map(|(a, e)| a.abs_diff(e))
+                .max()
+                .unwrap_or(0);
+        max_channel_diff = max_channel_diff.max(channel_diff);
+
+        let failing = channel_diff > tolerance.channel && yiq_delta(a, e) > threshold;
+        if failing {
+            failing_pixels += 1;
+            diff.put_pixel(x, y, Rgba([255, 0, 0, 255]));
+        } else {
+            let gray = (255.0 - (255.0 - luma(e)) * 0.1) as u8;
+            diff.put_pixel(x, y, Rgba([gray, gray, gray, 255]));
+        }
+    }
+
+    Comparison {
+        failing_pixels,
+        total_pixels: actual.width() * actual.height(),
+        max_channel_diff,
+        diff,
+    }
+}
+
+fn luma(pixel: &Rgba<u8>) -> f32 {
+    let [r, g, b, _] = blend_on_white(pixel);
+    r * 0.2988953 + g * 0.5866225 + b * 0.1144822
+}
+
+/// Color distance in YIQ space, weighted the way the eye perceives it
+/// (Kotsarenko and Ramos, "Measuring perceived color difference using YIQ").
+fn yiq_delta(a: &Rgba<u8>, b: &Rgba<u8>) -> f32 {
+    let [r1, g1, b1, _] = blend_on_white(a);
+    let [r2, g2, b2, _] = blend_on_white(b);
+    let (dr, dg, db) = (r1 - r2, g1 - g2, b1 - b2);
+
+    let y = dr * 0.2988953 + dg * 0.5866225 + db * 0.1144822;
+    let i = dr * 0.595978 - dg * 0.2741761 - db * 0.3218019;
+    let q = dr * 0.2114702 - dg * 0.5226171 + db * 0.3111469;
+
+    0.5053 * y * y + 0.299 * i * i + 0.1957 * q * q
+}
+
+fn blend_on_white(pixel: &Rgba<u8>) -> [f32; 4] {
+    let alpha = pixel.0[3] as f32 / 255.0;
+    let blend = |c: u8| 255.0 + (c as f32 - 255.0) * alpha;
+
+    [
+        blend(pixel.0[0]),
+        blend(pixel.0[1]),
+        blend(pixel.0[2]),
+        alpha,
+    ]
+}
@@ -0,0 +1,248 @@
+//! Renders small scenes offscreen and compares them against the reference
+//! images in `tests/golden/references`. Runs without the libtest harness so
+//! glfw lives on the main thread.
+//!
+//! Set `UPDATE_GOLDEN=1` to (re)write the references from the current output.
+//! Not getting a GL context is a failure, set `SKIP_GOLDEN=1` to skip the
+//! scenes on machines that can't render at all.
+
+mod compare;
+
+use std::{
+    env, fs, mem,
+    path::{Path, PathBuf},
+    process, ptr,
+};
+
+use ashen_engine::graphics::context::GlContext;
+use ashen_engine::graphics::gl_wrapper::{BufferObject, Vao, VertexAttribute};
+use ashen_engine::graphics::shaders::Shader;
+use ashen_engine::graphics::textures::{Texture, TextureFiltering, TextureType, TextureWrapping};
+use ashen_engine::graphics::uniform::TextureUnit;
+use ashen_engine::headless::HeadlessContext;
+use gl::types::{GLfloat, GLsizei};
+
+use compare::{compare, Tolerance};
+
+const WIDTH: u32 = 256;
+const HEIGHT: u32 = 256;
+
+struct Scene {
+    name: &'static str,
+    tolerance: Tolerance,
+    draw: fn(&GlContext),
+}
+
+const SCENES: &[Scene] = &[
+    Scene {
+        name: "clear_color",
+        tolerance: Tolerance {
+            channel: 0,
+            perceptual: 0.0,
+            max_failing: 0.0,
+        },
+        draw: clear_color,
+    },
+    Scene {
+        name: "textured_quad",
+        tolerance: Tolerance {
+            channel: 2,
+            perceptual: 0.1,
+            max_failing: 0.001,
+        },
+        draw: textured_quad,
+    },
+];
+
+fn main() {
+    if env::var_os("SKIP_GOLDEN").is_some() {
+        println!("golden image tests skipped, SKIP_GOLDEN is set");
+        return;
+    }
+
+    let context = match HeadlessContext::new(WIDTH, HEIGHT) {
+        Ok(context) => context,
+        Err(e) => {
+            eprintln!(
+                "golden: no OpenGL context ({}), set SKIP_GOLDEN=1 to skip these tests",
+                e
+            );
+            process::exit(1);
+        }
+    };
+    context.framebuffer().bind();
+
+    let update = env::var_os("UPDATE_GOLDEN").is_some();
+    if update {
+        fs::create_dir_all(references_dir()).unwrap();
+    }
+    let mut failures = Vec::new();
+
+    for scene in SCENES {
+        (scene.draw)(context.context());
+        let actual = context.read_image();
+        let reference = references_dir().join(format!("{}.png", scene.name));
+
+        if update {
+            ashen_engine::graphics::screenshot::save(&actual, &reference).unwrap();
+            println!("golden {} ... updated", scene.name);
+            continue;
+        }
+
+        let expected = match image::open(&reference) {
+            Ok(expected) => expected.into_rgba8(),
+            Err(e) => {
+                let actual_path = output_path(scene.name, "actual");
+                actual.save(&actual_path).unwrap();
+                failures.push(format!(
+                    "{}: can't read {} ({}), output written to {}, rerun with UPDATE_GOLDEN=1 to accept it",
+                    scene.name,
+                    reference.display(),
+                    e,
+                    actual_path.display()
+                ));
+                continue;
+            }
+        };
+
+        if expected.dimensions() != actual.dimensions() {
+            failures.push(format!(
+                "{}: reference is {:?} but rendered {:?}",
+                scene.name,
+                expected.dimensions(),
+                actual.dimensions()
+            ));
+            continue;
+        }
+
+        let comparison = compare(&actual, &expected, &scene.tolerance);
+        if comparison.passed(&scene.tolerance) {
+            println!("golden {} ... ok", scene.name);
+            continue;
+        }
+
+        let actual_path = output_path(scene.name, "actual");
+        let diff_path = output_path(scene.name, "diff");
+        actual.save(&actual_path).unwrap();
+        comparison.diff.save(&diff_path).unwrap();
+
+        failures.push(format!(
+            "{}: {} of {} pixels differ (max channel difference {}), see {} and {}",
+            scene.name,
+            comparison.failing_pixels,
+            comparison.total_pixels,
+            comparison.max_channel_diff,
+            actual_path.display(),
+            diff_path.display()
+        ));
+    }
+
+    if !failures.is_empty() {
+        for failure in &failures {
+            eprintln!("golden {}", failure);
+        }
+        process::exit(1);
+    }
+}
+
+fn references_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden/references")
+}
+
+fn output_path(scene: &str, kind: &str) -> PathBuf {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("golden");
+    fs::create_dir_all(&dir).unwrap();
+    dir.join(format!("{}.{}.png", scene, kind))
+}
+
+fn clear_color(_: &GlContext) {
+    unsafe {
+        gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+        gl::Clear(gl::COLOR_BUFFER_BIT);
+    }
+}
+
+/// The engine tester's quad, frozen at a fixed rotation.
+fn textured_quad(context: &GlContext) {
+    let vertices: [f32; 32] = [
+        // positions       // colors        // texture coords
+        0.5, 0.5, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, // top right
+        0.5, -0.5, 0.0, 0.0, 1.0, 0.0, 1.0, 0.0, // bottom right
+        -0.5, -0.5, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, // bottom left
+        -0.5, 0.5, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0, // top left
+    ];
+    let indices = [
+        0, 1, 3, // first Triangle
+        1, 2, 3, // second Triangle
+    ];
+
+    let mut shader = Shader::new(context);
+    shader.load_fragment_shader(&asset("shader.fs")).unwrap();
+    shader.load_vertex_shader(&asset("shader.vs")).unwrap();
+    shader.link_program().unwrap();
+
+    let vao = Vao::new(context);
+    vao.bind();
+
+    let vbo = BufferObject::new(context, gl::ARRAY_BUFFER, gl::STATIC_DRAW);
+    vbo.bind();
+    vbo.store_f32_data(&vertices);
+
+    let ebo = BufferObject::new(context, gl::ELEMENT_ARRAY_BUFFER, gl::STATIC_DRAW);
+    ebo.bind();
+    ebo.store_i32_data(&indices);
+
+    let stride = 8 * mem::size_of::<GLfloat>() as GLsizei;
+    let float = mem::size_of::<GLfloat>();
+    VertexAttribute::new(0, 3, gl::FLOAT, gl::FALSE, stride, 0).enable();
+    VertexAttribute::new(1, 3, gl::FLOAT, gl::FALSE, stride, 3 * float).enable();
+    VertexAttribute::new(2, 2, gl::FLOAT, gl::FALSE, stride, 6 * float).enable();
+
+    let texture = load_texture(context, "wall.jpg");
+    let texture_2 = load_texture(context, "awesomeface.png");
+
+    let (sin, cos) = 0.5f32.sin_cos();
+    #[rustfmt::skip]
+    let transform = glm::mat4(
+        cos, sin, 0.0, 0.0,
+        -sin, cos, 0.0, 0.0,
+        0.0, 0.0, 1.0, 0.0,
+        0.0, 0.0, 0.0, 1.0,
+    );
+
+    shader.use_program();
+    shader.set("texture1", TextureUnit(0));
+    shader.set("texture2", TextureUnit(1));
+    shader.set("transform", transform);
+
+    unsafe {
+        gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+        gl::Clear(gl::COLOR_BUFFER_BIT);
+
+        gl::ActiveTexture(gl::TEXTURE0);
+        gl::BindTexture(gl::TEXTURE_2D, texture.id());
+        gl::ActiveTexture(gl::TEXTURE1);
+        gl::BindTexture(gl::TEXTURE_2D, texture_2.id());
+
+        gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, ptr::null());
+    }
+}
+
+fn asset(name: &str) -> String {
+    format!(
+        "{}/engine-tester/assets/{}",
+        env!("CARGO_MANIFEST_DIR"),
+        name
+    )
+}
+
+fn load_texture(context: &GlContext, name: &str) -> Texture {
+    Texture::new(
+        context,
+        &asset(name),
+        TextureType::Texture2D,
+        TextureWrapping::Mirror,
+        TextureFiltering::Near,
+        TextureFiltering::None,
+    )
+}